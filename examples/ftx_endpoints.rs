@@ -1,5 +1,7 @@
-use chrono::Local;
+use chrono::Utc;
 use ftx_rs::api::*;
+use ftx_rs::candles::Resolution;
+use rust_decimal::Decimal;
 
 fn main() {
     println!("helo");
@@ -46,9 +48,9 @@ fn main() {
         }
     }
 
-    let candles = api
-        .markets
-        .get_historical_prices("BTC-PERP", 300, 0, Local::now().timestamp());
+    let candles =
+        api.markets
+            .get_historical_prices("BTC-PERP", Resolution::FiveMinutes, ..Utc::now());
     match candles {
         Ok(c) => {
             println!("candles: {:?}", c);
@@ -112,7 +114,7 @@ fn main() {
 
     let transfer_info = api.subaccounts.transfer_subaccounts(
         "USDT".to_string(),
-        10 as f64,
+        Decimal::from(10),
         "main".to_string(),
         "flow".to_string(),
     );
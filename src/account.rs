@@ -1,6 +1,8 @@
 use crate::client::Client;
 use crate::errors::*;
 use crate::model;
+use crate::pagination::paginate;
+use crate::timestamps::{with_query, TimeRange};
 
 #[derive(Clone)]
 pub struct AccountClient {
@@ -14,26 +16,78 @@ impl AccountClient {
         }
     }
 
+    pub fn for_subaccount<S>(&self, subaccount: S) -> Self
+    where
+        S: Into<String>,
+    {
+        AccountClient {
+            client: self.client.for_subaccount(subaccount),
+        }
+    }
+
+    pub fn for_main_account(&self) -> Self {
+        AccountClient {
+            client: self.client.for_main_account(),
+        }
+    }
+
     pub fn get_account(&self) -> Result<model::AccountInfo> {
         let payload: String = format!("{}", "{}");
-        let data = self.client.get_signed("/account".into(), payload, None)?;
+        let data = self.client.get_signed("/account".into(), payload)?;
         let account: model::Account = serde_json::from_str(data.as_str())?;
         Ok(account.result)
     }
 
     pub fn get_positions(&self) -> Result<Vec<model::Position>> {
         let payload: String = format!("{}", "{}");
-        let data = self.client.get_signed("/positions".into(), payload, None)?;
+        let data = self.client.get_signed("/positions".into(), payload)?;
         let positions: model::Positions = serde_json::from_str(data.as_str())?;
         Ok(positions.result)
     }
 
     pub fn change_account_leverage(&self, leverage: i64) -> Result<bool> {
         let payload = json!({ "leverage": leverage });
-        let data =
-            self.client
-                .post_signed("/account/leverage".into(), payload.to_string(), None)?;
+        let data = self
+            .client
+            .post_signed("/account/leverage".into(), payload.to_string())?;
         let r: model::AnotherOption = serde_json::from_str(data.as_str())?;
         Ok(r.success)
     }
+
+    // positive payments were paid by the account, negative ones received
+    pub fn get_funding_payments<R>(
+        &self,
+        future: Option<String>,
+        range: R,
+    ) -> Result<Vec<model::FundingPayment>>
+    where
+        R: Into<TimeRange>,
+    {
+        let payload: String = "{}".into();
+        let mut params = range.into().params();
+        if let Some(f) = future {
+            params.push(("future", f));
+        }
+        let endpoint = with_query("/funding_payments".into(), &params);
+        let data = self.client.get_signed(endpoint, payload)?;
+        let payments: model::ResultData<Vec<model::FundingPayment>> =
+            serde_json::from_str(data.as_str())?;
+        Ok(payments.result)
+    }
+
+    pub fn get_all_funding_payments<R>(
+        &self,
+        future: Option<String>,
+        range: R,
+    ) -> Result<Vec<model::FundingPayment>>
+    where
+        R: Into<TimeRange>,
+    {
+        paginate(
+            range.into(),
+            |page| self.get_funding_payments(future.clone(), page),
+            |payment| payment.time,
+            |payment| payment.id,
+        )
+    }
 }
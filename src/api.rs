@@ -1,8 +1,13 @@
 use crate::account::*;
+use crate::convert::*;
 use crate::fills::*;
 use crate::futures::*;
+use crate::leveraged_tokens::*;
 use crate::markets::*;
+use crate::options::*;
 use crate::orders::*;
+use crate::spot_margin::*;
+use crate::staking::*;
 use crate::subaccounts::*;
 use crate::wallet::*;
 
@@ -15,6 +20,11 @@ pub struct Ftx {
     pub wallet: WalletClient,
     pub orders: OrdersClient,
     pub fills: FillsClient,
+    pub spot_margin: SpotMarginClient,
+    pub convert: ConvertClient,
+    pub leveraged_tokens: LeveragedTokensClient,
+    pub options: OptionsClient,
+    pub staking: StakingClient,
 }
 
 impl Ftx {
@@ -27,6 +37,61 @@ impl Ftx {
             wallet: WalletClient::new(api_key.clone(), secret_key.clone()),
             orders: OrdersClient::new(api_key.clone(), secret_key.clone()),
             fills: FillsClient::new(api_key.clone(), secret_key.clone()),
+            spot_margin: SpotMarginClient::new(api_key.clone(), secret_key.clone()),
+            convert: ConvertClient::new(api_key.clone(), secret_key.clone()),
+            leveraged_tokens: LeveragedTokensClient::new(api_key.clone(), secret_key.clone()),
+            options: OptionsClient::new(api_key.clone(), secret_key.clone()),
+            staking: StakingClient::new(api_key.clone(), secret_key.clone()),
+        }
+    }
+
+    // A copy of this `Ftx` whose authenticated clients act on `subaccount`,
+    // so one master key can drive several subaccounts side by side. Subaccount
+    // management only works from the main account, so `subaccounts` stays as is.
+    pub fn for_subaccount<S>(&self, subaccount: S) -> Self
+    where
+        S: Into<String>,
+    {
+        let subaccount: String = subaccount.into();
+        Ftx {
+            markets: self.markets.clone(),
+            subaccounts: self.subaccounts.clone(),
+            futures: self.futures.clone(),
+            account: self.account.for_subaccount(subaccount.as_str()),
+            wallet: self.wallet.for_subaccount(subaccount.as_str()),
+            orders: self.orders.for_subaccount(subaccount.as_str()),
+            fills: self.fills.for_subaccount(subaccount.as_str()),
+            spot_margin: self.spot_margin.for_subaccount(subaccount.as_str()),
+            convert: self.convert.for_subaccount(subaccount.as_str()),
+            leveraged_tokens: self.leveraged_tokens.for_subaccount(subaccount.as_str()),
+            options: self.options.for_subaccount(subaccount.as_str()),
+            staking: self.staking.for_subaccount(subaccount.as_str()),
+        }
+    }
+
+    pub fn for_main_account(&self) -> Self {
+        Ftx {
+            markets: self.markets.clone(),
+            subaccounts: self.subaccounts.clone(),
+            futures: self.futures.clone(),
+            account: self.account.for_main_account(),
+            wallet: self.wallet.for_main_account(),
+            orders: self.orders.for_main_account(),
+            fills: self.fills.for_main_account(),
+            spot_margin: self.spot_margin.for_main_account(),
+            convert: self.convert.for_main_account(),
+            leveraged_tokens: self.leveraged_tokens.for_main_account(),
+            options: self.options.for_main_account(),
+            staking: self.staking.for_main_account(),
+        }
+    }
+
+    // `name` is a subaccount nickname or MAIN_ACCOUNT
+    pub fn for_account(&self, name: &str) -> Self {
+        if name == MAIN_ACCOUNT {
+            self.for_main_account()
+        } else {
+            self.for_subaccount(name)
         }
     }
 }
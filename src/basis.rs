@@ -0,0 +1,156 @@
+use crate::errors::*;
+use crate::futures::FuturesClient;
+use crate::markets::MarketsClient;
+use crate::model;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+const SECONDS_PER_YEAR: i64 = 365 * 24 * 60 * 60;
+// FTX perpetuals pay funding every hour
+const FUNDING_PERIODS_PER_YEAR: i64 = 365 * 24;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BasisInfo {
+    pub future: String,
+    pub underlying: String,
+    pub perpetual: bool,
+    pub expiry: Option<DateTime<Utc>>,
+    pub spot: Decimal,
+    pub mark: Decimal,
+    pub basis: Decimal,
+    pub basis_pct: Decimal,
+    pub annualized_yield: Option<Decimal>,
+    pub next_funding_rate: Option<Decimal>,
+    pub predicted_funding_apr: Option<Decimal>,
+}
+
+#[derive(Clone)]
+pub struct BasisCalculator {
+    futures: FuturesClient,
+    markets: MarketsClient,
+}
+
+impl BasisCalculator {
+    pub fn new(futures: FuturesClient, markets: MarketsClient) -> Self {
+        BasisCalculator { futures, markets }
+    }
+
+    // spot price from the `<UNDERLYING>/USD` market, falling back to the futures index
+    pub fn get_spot_price<S>(&self, underlying: S, index: Option<Decimal>) -> Result<Decimal>
+    where
+        S: Into<String>,
+    {
+        let underlying: String = underlying.into();
+        let spot = self
+            .markets
+            .get_market(format!("{}/USD", underlying))
+            .ok()
+            .and_then(|m| m.price.or(m.last));
+        match spot.or(index) {
+            Some(p) => Ok(p),
+            None => bail!(format!("No spot price for {}", underlying)),
+        }
+    }
+
+    // basis of every live perpetual and dated future on `underlying`
+    pub fn get_basis<S>(&self, underlying: S) -> Result<Vec<BasisInfo>>
+    where
+        S: Into<String>,
+    {
+        let underlying: String = underlying.into();
+        let futures: Vec<model::Future> = self
+            .futures
+            .get_futures()?
+            .into_iter()
+            .filter(|f| f.underlying == underlying && f.enabled && !f.expired)
+            .filter(|f| f.market_type == "future" || f.market_type == "perpetual")
+            .collect();
+        if futures.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let spot = self.get_spot_price(underlying.as_str(), futures[0].index)?;
+        let now = Utc::now();
+        let mut basis = Vec::new();
+        for future in futures {
+            let mark = match future.mark.or(future.last) {
+                Some(m) => m,
+                None => continue,
+            };
+            let next_funding_rate = if future.perpetual {
+                self.futures
+                    .get_stats(future.name.as_str())?
+                    .next_funding_rate
+            } else {
+                None
+            };
+            basis.push(BasisInfo {
+                future: future.name,
+                underlying: future.underlying,
+                perpetual: future.perpetual,
+                expiry: future.expiry,
+                spot,
+                mark,
+                basis: mark - spot,
+                basis_pct: basis_pct(spot, mark),
+                annualized_yield: future
+                    .expiry
+                    .and_then(|expiry| annualized_yield(spot, mark, now, expiry)),
+                next_funding_rate,
+                predicted_funding_apr: next_funding_rate.map(funding_apr),
+            });
+        }
+        Ok(basis)
+    }
+}
+
+pub fn basis_pct(spot: Decimal, price: Decimal) -> Decimal {
+    if spot.is_zero() {
+        return Decimal::ZERO;
+    }
+    (price - spot) / spot
+}
+
+// carry earned by selling `price` and buying `spot` until expiry, as a yearly rate
+pub fn annualized_yield(
+    spot: Decimal,
+    price: Decimal,
+    now: DateTime<Utc>,
+    expiry: DateTime<Utc>,
+) -> Option<Decimal> {
+    let seconds = (expiry - now).num_seconds();
+    if seconds <= 0 || spot.is_zero() {
+        return None;
+    }
+    Some(basis_pct(spot, price) * Decimal::from(SECONDS_PER_YEAR) / Decimal::from(seconds))
+}
+
+pub fn funding_apr(hourly_rate: Decimal) -> Decimal {
+    hourly_rate * Decimal::from(FUNDING_PERIODS_PER_YEAR)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_annualized_yield() {
+        let now = Utc::now();
+        let expiry = now + Duration::days(73);
+        let y = annualized_yield(Decimal::from(100), Decimal::from(101), now, expiry).unwrap();
+        assert_eq!(y.round_dp(4), Decimal::from_str_exact("0.05").unwrap());
+        assert_eq!(
+            annualized_yield(Decimal::from(100), Decimal::from(101), expiry, now),
+            None
+        );
+    }
+
+    #[test]
+    fn test_funding_apr() {
+        let apr = funding_apr(Decimal::from_str_exact("0.0001").unwrap());
+        assert_eq!(apr, Decimal::from_str_exact("0.876").unwrap());
+    }
+}
@@ -0,0 +1,468 @@
+use crate::errors::*;
+use crate::model::{self, OrderStatus, Side, TriggerOrderType};
+use crate::orders::OrdersClient;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Leg {
+    TakeProfit,
+    Stop,
+}
+
+impl Leg {
+    pub fn sibling(&self) -> Leg {
+        match self {
+            Leg::TakeProfit => Leg::Stop,
+            Leg::Stop => Leg::TakeProfit,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BracketState {
+    // entry resting, nothing filled yet
+    Entering,
+    // position open and protected by both legs
+    Open,
+    Closed(Leg),
+    Cancelled,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BracketParams {
+    pub market: String,
+    pub side: Side,
+    pub size: Decimal,
+    // None enters with a market order
+    pub entry_price: Option<Decimal>,
+    pub take_profit_price: Decimal,
+    pub stop_price: Decimal,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Action {
+    PlaceLegs(Decimal),
+    Resize(Leg, Decimal),
+    Cancel(Leg),
+    CancelEntry,
+}
+
+// One entry with a take-profit and a stop around the position it builds. Legs
+// are reduce-only market trigger orders sized to what the entry has filled.
+#[derive(Debug, Clone)]
+pub struct Bracket {
+    pub params: BracketParams,
+    pub entry_id: i64,
+    pub entry_open: bool,
+    pub entry_filled: Decimal,
+    pub exited: Decimal,
+    // trigger order ids
+    pub legs: HashMap<Leg, i64>,
+    // orders sent by a triggered leg
+    pub exit_orders: HashMap<i64, Leg>,
+    pub state: BracketState,
+}
+
+impl Bracket {
+    fn new(params: BracketParams, entry_id: i64) -> Self {
+        Bracket {
+            params,
+            entry_id,
+            entry_open: true,
+            entry_filled: Decimal::ZERO,
+            exited: Decimal::ZERO,
+            legs: HashMap::new(),
+            exit_orders: HashMap::new(),
+            state: BracketState::Entering,
+        }
+    }
+
+    pub fn position(&self) -> Decimal {
+        (self.entry_filled - self.exited).max(Decimal::ZERO)
+    }
+
+    pub fn is_done(&self) -> bool {
+        matches!(
+            self.state,
+            BracketState::Closed(_) | BracketState::Cancelled
+        )
+    }
+
+    fn on_entry_fill(&mut self, size: Decimal) -> Vec<Action> {
+        self.entry_filled += size;
+        match self.state {
+            BracketState::Entering => {
+                self.state = BracketState::Open;
+                vec![Action::PlaceLegs(self.position())]
+            }
+            BracketState::Open => {
+                // a triggered leg is an order now and closes what it can on its own
+                let triggered: Vec<Leg> = self.exit_orders.values().cloned().collect();
+                [Leg::TakeProfit, Leg::Stop]
+                    .iter()
+                    .filter(|l| !triggered.contains(l))
+                    .map(|l| Action::Resize(*l, self.position()))
+                    .collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn on_entry_closed(&mut self) -> Vec<Action> {
+        self.entry_open = false;
+        if self.state == BracketState::Entering {
+            self.state = BracketState::Cancelled;
+        }
+        Vec::new()
+    }
+
+    // could `order` have been sent by one of this bracket's legs; only the
+    // legs' triggers can say for sure
+    fn is_exit_order(&self, order: &model::Order) -> bool {
+        self.state == BracketState::Open
+            && !self.legs.is_empty()
+            && order.id != self.entry_id
+            && !self.exit_orders.contains_key(&order.id)
+            && order.market == self.params.market
+            && order.side == self.params.side.opposite()
+            && order.reduce_only
+    }
+
+    fn on_exit_fill(&mut self, leg: Leg, size: Decimal) -> Vec<Action> {
+        if self.is_done() {
+            return Vec::new();
+        }
+        self.exited += size;
+        let mut actions = Vec::new();
+        // once a leg has triggered, adding to the position makes no sense
+        if self.entry_open {
+            self.entry_open = false;
+            actions.push(Action::CancelEntry);
+        }
+        if self.position().is_zero() {
+            self.state = BracketState::Closed(leg);
+            actions.push(Action::Cancel(leg.sibling()));
+        } else {
+            actions.push(Action::Resize(leg.sibling(), self.position()));
+        }
+        actions
+    }
+}
+
+// Client-side OCO: places brackets and keeps their legs in step with fills
+// from the `fills` and `orders` websocket channels.
+pub struct BracketManager {
+    orders: OrdersClient,
+    brackets: Vec<Bracket>,
+    // exit-side fills of orders not yet seen on the `orders` channel: size, last price
+    unmatched: HashMap<i64, (Decimal, Decimal)>,
+}
+
+impl BracketManager {
+    pub fn new(orders: OrdersClient) -> Self {
+        BracketManager {
+            orders,
+            brackets: Vec::new(),
+            unmatched: HashMap::new(),
+        }
+    }
+
+    pub fn brackets(&self) -> &[Bracket] {
+        &self.brackets
+    }
+
+    pub fn bracket(&self, entry_id: i64) -> Option<&Bracket> {
+        self.brackets.iter().find(|b| b.entry_id == entry_id)
+    }
+
+    // place the entry; legs follow its first fill. Returns the entry order id.
+    pub fn place(&mut self, params: BracketParams) -> Result<i64> {
+        let order_type = match params.entry_price {
+            Some(_) => "limit",
+            None => "market",
+        };
+        let entry = self.orders.place_order(
+            params.market.as_str(),
+            params.side.as_str().to_string(),
+            params.entry_price,
+            order_type.to_string(),
+            params.size,
+            false,
+            false,
+            false,
+            None,
+            false,
+        )?;
+        // fills, including an immediate one, come through `on_fill`
+        self.brackets.push(Bracket::new(params, entry.id));
+        Ok(entry.id)
+    }
+
+    // cancel the entry and both legs, leaving any position as it is
+    pub fn cancel(&mut self, entry_id: i64) -> Result<()> {
+        let index = match self.brackets.iter().position(|b| b.entry_id == entry_id) {
+            Some(i) => i,
+            None => bail!(format!("No bracket for order {}", entry_id)),
+        };
+        let bracket = &mut self.brackets[index];
+        let mut actions = vec![Action::Cancel(Leg::TakeProfit), Action::Cancel(Leg::Stop)];
+        if bracket.entry_open {
+            bracket.entry_open = false;
+            actions.push(Action::CancelEntry);
+        }
+        bracket.state = BracketState::Cancelled;
+        self.apply(index, actions)
+    }
+
+    pub fn on_fill(&mut self, fill: &model::Fill) -> Result<()> {
+        if let Some(index) = self
+            .brackets
+            .iter()
+            .position(|b| b.entry_id == fill.order_id)
+        {
+            let actions = self.brackets[index].on_entry_fill(fill.size);
+            return self.apply(index, actions);
+        }
+
+        let found = self
+            .brackets
+            .iter()
+            .enumerate()
+            .find_map(|(index, b)| b.exit_orders.get(&fill.order_id).map(|leg| (index, *leg)));
+        if let Some((index, leg)) = found {
+            let actions = self.brackets[index].on_exit_fill(leg, fill.size);
+            return self.apply(index, actions);
+        }
+
+        // may be a triggered leg whose order update hasn't arrived yet
+        let exit_side = self.brackets.iter().any(|b| {
+            b.state == BracketState::Open
+                && b.params.market == fill.market
+                && b.params.side.opposite() == fill.side
+        });
+        if exit_side {
+            let pending = self.unmatched.entry(fill.order_id).or_default();
+            pending.0 += fill.size;
+            pending.1 = fill.price;
+        }
+        Ok(())
+    }
+
+    // Entry closes end the entry phase. A reduce-only order on the exit side
+    // takes over a leg's fills once that leg's triggers list it; other brackets
+    // in the market, or manual orders, never get credited by guess.
+    pub fn on_order(&mut self, order: &model::Order) -> Result<()> {
+        if let Some(index) = self.brackets.iter().position(|b| b.entry_id == order.id) {
+            if order.status != OrderStatus::Closed {
+                return Ok(());
+            }
+            let actions = self.brackets[index].on_entry_closed();
+            return self.apply(index, actions);
+        }
+
+        let mut found = None;
+        for index in self.exit_candidates(order) {
+            if let Some(leg) = self.lookup_leg(index, order.id)? {
+                found = Some((index, leg));
+                break;
+            }
+        }
+        let (index, leg) = match found {
+            Some(f) => f,
+            None => {
+                // not sent by a leg, or not listed yet and retried on the next update
+                if order.status == OrderStatus::Closed {
+                    self.unmatched.remove(&order.id);
+                }
+                return Ok(());
+            }
+        };
+        self.brackets[index].exit_orders.insert(order.id, leg);
+        match self.unmatched.remove(&order.id) {
+            Some((size, _)) => {
+                let actions = self.brackets[index].on_exit_fill(leg, size);
+                self.apply(index, actions)
+            }
+            None => Ok(()),
+        }
+    }
+
+    // every bracket `order` may belong to, as several can share a market
+    fn exit_candidates(&self, order: &model::Order) -> Vec<usize> {
+        self.brackets
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| b.is_exit_order(order))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    // ask each leg of the bracket whether it sent `order_id`
+    fn lookup_leg(&self, index: usize, order_id: i64) -> Result<Option<Leg>> {
+        for (leg, id) in self.brackets[index].legs.iter() {
+            let triggers = self.orders.get_trigger_order_triggers(*id)?;
+            if sent_by(&triggers, order_id) {
+                return Ok(Some(*leg));
+            }
+        }
+        Ok(None)
+    }
+
+    fn apply(&mut self, index: usize, actions: Vec<Action>) -> Result<()> {
+        let bracket = &mut self.brackets[index];
+        for action in actions {
+            match action {
+                Action::PlaceLegs(size) => {
+                    let exit = bracket.params.side.opposite();
+                    for (leg, option_type, price) in [
+                        (
+                            Leg::TakeProfit,
+                            TriggerOrderType::TakeProfit,
+                            bracket.params.take_profit_price,
+                        ),
+                        (Leg::Stop, TriggerOrderType::Stop, bracket.params.stop_price),
+                    ] {
+                        let mut order = model::NewTriggerOrder::new(
+                            bracket.params.market.as_str(),
+                            exit,
+                            option_type,
+                            size,
+                        );
+                        order.reduce_only = true;
+                        order.trigger_price = Some(price);
+                        let placed = match self.orders.place_trigger_order(&order) {
+                            Ok(placed) => placed,
+                            Err(e) => {
+                                // don't leave a lone leg behind that nothing tracks
+                                for (_, id) in bracket.legs.drain() {
+                                    let _ = self.orders.cancel_trigger_order(id);
+                                }
+                                return Err(e);
+                            }
+                        };
+                        bracket.legs.insert(leg, placed.id);
+                    }
+                }
+                Action::Resize(leg, size) => {
+                    if let Some(id) = bracket.legs.get(&leg).cloned() {
+                        let modified = self
+                            .orders
+                            .modify_trigger_order(id, size, None, None, None)?;
+                        bracket.legs.insert(leg, modified.id);
+                    }
+                }
+                Action::Cancel(leg) => {
+                    if let Some(id) = bracket.legs.remove(&leg) {
+                        self.orders.cancel_trigger_order(id)?;
+                    }
+                }
+                Action::CancelEntry => {
+                    self.orders.cancel_order(bracket.entry_id)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn sent_by(triggers: &[model::TriggerOrderTrigger], order_id: i64) -> bool {
+    triggers.iter().any(|t| t.order_id == Some(order_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bracket() -> Bracket {
+        let params = BracketParams {
+            market: "BTC-PERP".into(),
+            side: Side::Buy,
+            size: Decimal::from(3),
+            entry_price: Some(Decimal::from(100)),
+            take_profit_price: Decimal::from(110),
+            stop_price: Decimal::from(95),
+        };
+        Bracket::new(params, 1)
+    }
+
+    #[test]
+    fn test_partial_fills() {
+        let mut b = bracket();
+        assert_eq!(
+            b.on_entry_fill(Decimal::ONE),
+            vec![Action::PlaceLegs(Decimal::ONE)]
+        );
+        assert_eq!(
+            b.on_entry_fill(Decimal::ONE),
+            vec![
+                Action::Resize(Leg::TakeProfit, Decimal::TWO),
+                Action::Resize(Leg::Stop, Decimal::TWO)
+            ]
+        );
+
+        // stop triggers and fills in two parts; the entry remainder is pulled
+        assert_eq!(
+            b.on_exit_fill(Leg::Stop, Decimal::ONE),
+            vec![
+                Action::CancelEntry,
+                Action::Resize(Leg::TakeProfit, Decimal::ONE)
+            ]
+        );
+        assert_eq!(
+            b.on_exit_fill(Leg::Stop, Decimal::ONE),
+            vec![Action::Cancel(Leg::TakeProfit)]
+        );
+        assert_eq!(b.state, BracketState::Closed(Leg::Stop));
+    }
+
+    #[test]
+    fn test_exit_order_leg() {
+        let exit = |id: i64, side: &str, reduce_only: bool| -> model::Order {
+            serde_json::from_value(json!({
+                "id": id, "clientId": null, "market": "BTC-PERP", "future": "BTC-PERP",
+                "type": "market", "side": side, "price": null, "size": 1, "status": "new",
+                "filledSize": 0, "remainingSize": 1, "avgFillPrice": null,
+                "reduceOnly": reduce_only, "ioc": true, "postOnly": false,
+                "liquidation": false, "createdAt": null
+            }))
+            .unwrap()
+        };
+        let mut b = bracket();
+        // no legs yet
+        assert!(!b.is_exit_order(&exit(7, "sell", true)));
+
+        b.on_entry_fill(Decimal::ONE);
+        b.legs.insert(Leg::TakeProfit, 10);
+        b.legs.insert(Leg::Stop, 11);
+        assert!(b.is_exit_order(&exit(7, "sell", true)));
+        assert!(!b.is_exit_order(&exit(7, "sell", false)));
+        assert!(!b.is_exit_order(&exit(7, "buy", true)));
+        assert!(!b.is_exit_order(&exit(1, "sell", true)));
+
+        // a second bracket in the market matches just as well, so the
+        // triggers decide
+        let mut manager = BracketManager::new(OrdersClient::new(None, None));
+        let mut other = b.clone();
+        other.entry_id = 2;
+        manager.brackets = vec![b, other];
+        assert_eq!(manager.exit_candidates(&exit(7, "sell", true)), vec![0, 1]);
+
+        let triggers: Vec<model::TriggerOrderTrigger> = serde_json::from_value(json!([
+            {"time": "2019-03-05T09:56:55.728933+00:00", "orderSize": 1, "filledSize": 1,
+             "orderId": 7, "error": null},
+            {"time": "2019-03-05T09:57:55.728933+00:00", "orderSize": null, "filledSize": null,
+             "orderId": null, "error": "Price band"}
+        ]))
+        .unwrap();
+        assert!(sent_by(&triggers, 7));
+        assert!(!sent_by(&triggers, 8));
+    }
+
+    #[test]
+    fn test_unfilled_entry() {
+        let mut b = bracket();
+        assert!(b.on_entry_closed().is_empty());
+        assert_eq!(b.state, BracketState::Cancelled);
+    }
+}
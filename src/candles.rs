@@ -0,0 +1,432 @@
+use crate::errors::*;
+use crate::events::{TradeInfo, TradesData};
+use crate::model::CandleInfo;
+use crate::timestamps::{from_seconds, TimeRange};
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryFrom;
+
+// FTX returns at most 1501 candles per request
+pub const MAX_CANDLES_PER_REQUEST: i64 = 1500;
+
+// longest multi-day window FTX accepts
+pub const MAX_RESOLUTION_DAYS: u8 = 30;
+
+/// Candle window lengths supported by FTX.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Resolution {
+    FifteenSeconds,
+    OneMinute,
+    FiveMinutes,
+    FifteenMinutes,
+    OneHour,
+    FourHours,
+    OneDay,
+    // 2 to MAX_RESOLUTION_DAYS days, e.g. weekly bars; built with `Resolution::days`
+    Days(DayCount),
+}
+
+/// Day count of a multi-day `Resolution`, always within 2..=MAX_RESOLUTION_DAYS.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct DayCount(u8);
+
+impl DayCount {
+    pub fn get(&self) -> u8 {
+        self.0
+    }
+}
+
+impl Resolution {
+    // one day is always `OneDay`
+    pub fn days(days: u8) -> Result<Self> {
+        match days {
+            1 => Ok(Resolution::OneDay),
+            2..=MAX_RESOLUTION_DAYS => Ok(Resolution::Days(DayCount(days))),
+            d => bail!(format!("Unsupported resolution: {} days", d)),
+        }
+    }
+
+    pub fn seconds(&self) -> i64 {
+        match self {
+            Resolution::FifteenSeconds => 15,
+            Resolution::OneMinute => 60,
+            Resolution::FiveMinutes => 300,
+            Resolution::FifteenMinutes => 900,
+            Resolution::OneHour => 3600,
+            Resolution::FourHours => 14400,
+            Resolution::OneDay => 86400,
+            Resolution::Days(days) => 86400 * days.get() as i64,
+        }
+    }
+
+    pub fn duration(&self) -> Duration {
+        Duration::seconds(self.seconds())
+    }
+}
+
+impl From<Resolution> for i64 {
+    fn from(r: Resolution) -> Self {
+        r.seconds()
+    }
+}
+
+impl TryFrom<i64> for Resolution {
+    type Error = Error;
+
+    fn try_from(seconds: i64) -> Result<Self> {
+        match seconds {
+            15 => Ok(Resolution::FifteenSeconds),
+            60 => Ok(Resolution::OneMinute),
+            300 => Ok(Resolution::FiveMinutes),
+            900 => Ok(Resolution::FifteenMinutes),
+            3600 => Ok(Resolution::OneHour),
+            14400 => Ok(Resolution::FourHours),
+            s if s > 0 && s % 86400 == 0 && s / 86400 <= MAX_RESOLUTION_DAYS as i64 => {
+                Resolution::days((s / 86400) as u8)
+            }
+            s => bail!(format!("Unsupported resolution: {}", s)),
+        }
+    }
+}
+
+fn align(time: DateTime<Utc>, seconds: i64) -> DateTime<Utc> {
+    from_seconds(time.timestamp().div_euclid(seconds) * seconds)
+}
+
+// Split `start..=end` into back to back windows of at most MAX_CANDLES_PER_REQUEST candles.
+pub fn chunk_ranges(
+    resolution: Resolution,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Vec<TimeRange> {
+    let step = resolution.seconds();
+    let mut chunks = Vec::new();
+    let mut chunk_start = align(start, step);
+    while chunk_start <= end {
+        let chunk_end = std::cmp::min(
+            chunk_start + Duration::seconds(step * (MAX_CANDLES_PER_REQUEST - 1)),
+            end,
+        );
+        chunks.push(TimeRange::new(Some(chunk_start), Some(chunk_end)));
+        chunk_start = align(chunk_end, step) + Duration::seconds(step);
+    }
+    chunks
+}
+
+// Download `range` chunk by chunk with `fetch`, dropping the candles duplicated at chunk edges.
+pub(crate) fn fetch_chunked<F>(
+    resolution: Resolution,
+    range: TimeRange,
+    mut fetch: F,
+) -> Result<Vec<CandleInfo>>
+where
+    F: FnMut(TimeRange) -> Result<Vec<CandleInfo>>,
+{
+    let start = match range.start {
+        Some(s) => s,
+        None => bail!("A start time is required to download candles"),
+    };
+    let start = align(start, resolution.seconds());
+    let end = range.end.unwrap_or_else(Utc::now);
+
+    let mut candles: BTreeMap<DateTime<Utc>, CandleInfo> = BTreeMap::new();
+    for chunk in chunk_ranges(resolution, start, end) {
+        for candle in fetch(chunk)? {
+            if candle.start_time >= start && candle.start_time <= end {
+                candles.insert(candle.start_time, candle);
+            }
+        }
+    }
+    Ok(candles.into_values().collect())
+}
+
+/// Roll candles up into `timeframe` buckets aligned to the unix epoch.
+///
+/// Input must be sorted by `start_time`; the last bucket may be partial.
+pub fn resample(candles: &[CandleInfo], timeframe: Duration) -> Result<Vec<CandleInfo>> {
+    let step = timeframe.num_seconds();
+    if step <= 0 {
+        bail!("Resample timeframe must be at least one second");
+    }
+
+    let mut out: Vec<CandleInfo> = Vec::new();
+    for candle in candles {
+        let bucket = align(candle.start_time, step);
+        match out.last_mut() {
+            Some(last) if last.start_time == bucket => {
+                last.high = last.high.max(candle.high);
+                last.low = last.low.min(candle.low);
+                last.close = candle.close;
+                last.volume += candle.volume;
+            }
+            _ => out.push(CandleInfo {
+                start_time: bucket,
+                time: Decimal::from(bucket.timestamp() * 1000),
+                open: candle.open,
+                high: candle.high,
+                low: candle.low,
+                close: candle.close,
+                volume: candle.volume,
+            }),
+        }
+    }
+    Ok(out)
+}
+
+/// A bar closed by the `CandleAggregator`.
+#[derive(Debug, Clone)]
+pub struct ClosedCandle {
+    pub market: String,
+    pub resolution: Resolution,
+    pub candle: CandleInfo,
+}
+
+/// Builds OHLCV bars from the `trades` websocket channel.
+///
+/// Volume is quote volume (price * size) to match `get_historical_prices`.
+/// Intervals without trades are emitted as flat bars at the previous close.
+pub struct CandleAggregator {
+    resolutions: Vec<Resolution>,
+    open: HashMap<(String, Resolution), CandleInfo>,
+}
+
+impl CandleAggregator {
+    pub fn new(resolutions: Vec<Resolution>) -> Self {
+        CandleAggregator {
+            resolutions,
+            open: HashMap::new(),
+        }
+    }
+
+    // continue from the latest REST candle, trades before it are ignored
+    pub fn seed<S>(&mut self, market: S, resolution: Resolution, candles: &[CandleInfo])
+    where
+        S: Into<String>,
+    {
+        if let Some(last) = candles.iter().max_by_key(|c| c.start_time) {
+            self.open.insert((market.into(), resolution), last.clone());
+        }
+    }
+
+    pub fn current(&self, market: &str, resolution: Resolution) -> Option<&CandleInfo> {
+        self.open.get(&(market.to_string(), resolution))
+    }
+
+    pub fn on_trades(&mut self, trades: &TradesData) -> Vec<ClosedCandle> {
+        let mut closed = Vec::new();
+        for trade in &trades.data {
+            for resolution in self.resolutions.clone() {
+                closed.extend(self.on_trade(&trades.market, resolution, trade));
+            }
+        }
+        closed
+    }
+
+    // close every bar whose interval ended before `now`, e.g. from a timer
+    pub fn flush(&mut self, now: DateTime<Utc>) -> Vec<ClosedCandle> {
+        let mut closed = Vec::new();
+        let keys: Vec<(String, Resolution)> = self.open.keys().cloned().collect();
+        for (market, resolution) in keys {
+            let bucket = align(now, resolution.seconds());
+            closed.extend(self.roll(&market, resolution, bucket));
+        }
+        closed
+    }
+
+    fn on_trade(
+        &mut self,
+        market: &str,
+        resolution: Resolution,
+        trade: &TradeInfo,
+    ) -> Vec<ClosedCandle> {
+        let bucket = align(trade.time, resolution.seconds());
+        let key = (market.to_string(), resolution);
+        let closed = match self.open.get(&key) {
+            Some(bar) if bucket < bar.start_time => return Vec::new(),
+            Some(_) => self.roll(market, resolution, bucket),
+            None => {
+                self.open
+                    .insert(key.clone(), flat_candle(bucket, trade.price));
+                Vec::new()
+            }
+        };
+
+        if let Some(bar) = self.open.get_mut(&key) {
+            if bar.volume.is_zero() && bar.open == bar.close && bar.high == bar.low {
+                bar.open = trade.price;
+                bar.high = trade.price;
+                bar.low = trade.price;
+            }
+            bar.high = bar.high.max(trade.price);
+            bar.low = bar.low.min(trade.price);
+            bar.close = trade.price;
+            bar.volume += trade.price * trade.size;
+        }
+        closed
+    }
+
+    // close the open bar and fill empty intervals up to `bucket`, which becomes the open bar
+    fn roll(
+        &mut self,
+        market: &str,
+        resolution: Resolution,
+        bucket: DateTime<Utc>,
+    ) -> Vec<ClosedCandle> {
+        let key = (market.to_string(), resolution);
+        let mut closed = Vec::new();
+        let mut bar = match self.open.get(&key) {
+            Some(bar) if bar.start_time < bucket => bar.clone(),
+            _ => return closed,
+        };
+        while bar.start_time < bucket {
+            let next = flat_candle(bar.start_time + resolution.duration(), bar.close);
+            closed.push(ClosedCandle {
+                market: market.to_string(),
+                resolution,
+                candle: bar,
+            });
+            bar = next;
+        }
+        self.open.insert(key, bar);
+        closed
+    }
+}
+
+fn flat_candle(start_time: DateTime<Utc>, price: Decimal) -> CandleInfo {
+    CandleInfo {
+        start_time,
+        time: Decimal::from(start_time.timestamp() * 1000),
+        open: price,
+        high: price,
+        low: price,
+        close: price,
+        volume: Decimal::ZERO,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candle(seconds: i64, open: i64, close: i64) -> CandleInfo {
+        CandleInfo {
+            start_time: from_seconds(seconds),
+            time: Decimal::from(seconds * 1000),
+            open: Decimal::from(open),
+            high: Decimal::from(open.max(close)),
+            low: Decimal::from(open.min(close)),
+            close: Decimal::from(close),
+            volume: Decimal::from(1),
+        }
+    }
+
+    #[test]
+    fn test_resolution_days() {
+        assert_eq!(Resolution::try_from(86400).unwrap(), Resolution::OneDay);
+        assert_eq!(
+            Resolution::try_from(7 * 86400).unwrap(),
+            Resolution::days(7).unwrap()
+        );
+        assert_eq!(Resolution::days(1).unwrap(), Resolution::OneDay);
+        assert_eq!(Resolution::days(30).unwrap().seconds(), 30 * 86400);
+        assert!(Resolution::try_from(31 * 86400).is_err());
+        assert!(Resolution::try_from(86400 + 3600).is_err());
+        // zero days has no valid resolution, so nothing can divide by it
+        assert!(Resolution::days(0).is_err());
+        assert!(Resolution::try_from(0).is_err());
+        assert!(Resolution::days(31).is_err());
+    }
+
+    #[test]
+    fn test_chunk_ranges() {
+        let start = from_seconds(0);
+        let end = from_seconds(60 * 3000);
+        let chunks = chunk_ranges(Resolution::OneMinute, start, end);
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[0].end, Some(from_seconds(60 * 1499)));
+        assert_eq!(chunks[1].start, Some(from_seconds(60 * 1500)));
+        assert_eq!(chunks[2].end, Some(end));
+    }
+
+    #[test]
+    fn test_fetch_chunked_dedup() {
+        let range = TimeRange::new(Some(from_seconds(0)), Some(from_seconds(60 * 3000)));
+        let candles = fetch_chunked(Resolution::OneMinute, range, |chunk| {
+            // overlap one candle on each side like the exchange does
+            let s = chunk.start.unwrap().timestamp() - 60;
+            let e = chunk.end.unwrap().timestamp() + 60;
+            Ok((s / 60..=e / 60).map(|i| candle(i * 60, 1, 1)).collect())
+        })
+        .unwrap();
+        assert_eq!(candles.len(), 3001);
+        assert!(candles
+            .windows(2)
+            .all(|w| w[1].start_time - w[0].start_time == Duration::minutes(1)));
+    }
+
+    #[test]
+    fn test_resample() {
+        let candles: Vec<CandleInfo> = (0..10).map(|i| candle(i * 60, i, i + 1)).collect();
+        let bars = resample(&candles, Duration::minutes(5)).unwrap();
+        assert_eq!(bars.len(), 2);
+        assert_eq!(bars[0].open, Decimal::from(0));
+        assert_eq!(bars[0].close, Decimal::from(5));
+        assert_eq!(bars[1].high, Decimal::from(10));
+        assert_eq!(bars[1].volume, Decimal::from(5));
+        assert!(resample(&candles, Duration::zero()).is_err());
+    }
+
+    fn trades(market: &str, trades: &[(i64, i64, i64)]) -> TradesData {
+        let data: Vec<serde_json::Value> = trades
+            .iter()
+            .map(|(t, price, size)| {
+                json!({
+                    "id": t,
+                    "price": price,
+                    "size": size,
+                    "side": "buy",
+                    "liquidation": false,
+                    "time": from_seconds(*t).to_rfc3339(),
+                })
+            })
+            .collect();
+        serde_json::from_value(json!({
+            "channel": "trades",
+            "market": market,
+            "type": "update",
+            "data": data,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_aggregator_fills_empty_intervals() {
+        let mut agg = CandleAggregator::new(vec![Resolution::OneMinute]);
+        assert!(agg
+            .on_trades(&trades("BTC-PERP", &[(0, 100, 1), (30, 105, 2)]))
+            .is_empty());
+        let closed = agg.on_trades(&trades("BTC-PERP", &[(185, 99, 1)]));
+        assert_eq!(closed.len(), 3);
+        assert_eq!(closed[0].candle.high, Decimal::from(105));
+        assert_eq!(closed[0].candle.volume, Decimal::from(310));
+        assert_eq!(closed[1].candle.open, Decimal::from(105));
+        assert_eq!(closed[2].candle.volume, Decimal::ZERO);
+        let open = agg.current("BTC-PERP", Resolution::OneMinute).unwrap();
+        assert_eq!(open.start_time, from_seconds(180));
+        assert_eq!(open.open, Decimal::from(99));
+    }
+
+    #[test]
+    fn test_aggregator_seed_and_flush() {
+        let mut agg = CandleAggregator::new(vec![Resolution::OneMinute]);
+        agg.seed("ETH-PERP", Resolution::OneMinute, &[candle(0, 10, 11)]);
+        assert!(agg.on_trades(&trades("ETH-PERP", &[(-5, 1, 1)])).is_empty());
+        agg.on_trades(&trades("ETH-PERP", &[(10, 12, 1)]));
+        let closed = agg.flush(from_seconds(61));
+        assert_eq!(closed.len(), 1);
+        assert_eq!(closed[0].candle.open, Decimal::from(10));
+        assert_eq!(closed[0].candle.close, Decimal::from(12));
+        assert_eq!(closed[0].candle.volume, Decimal::from(13));
+    }
+}
@@ -11,6 +11,7 @@ static API_HOST: &'static str = "https://ftx.com/api";
 pub struct Client {
     api_key: String,
     secret_key: String,
+    subaccount: Option<String>,
     client: reqwest::Client,
 }
 
@@ -19,10 +20,34 @@ impl Client {
         Client {
             api_key: api_key.unwrap_or("".into()),
             secret_key: secret_key.unwrap_or("".into()),
+            subaccount: None,
             client: reqwest::Client::new(),
         }
     }
 
+    // same credentials, with every signed request routed to `subaccount`
+    pub fn for_subaccount<S>(&self, subaccount: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Client {
+            subaccount: Some(subaccount.into()),
+            ..self.clone()
+        }
+    }
+
+    // same credentials, back on the main account
+    pub fn for_main_account(&self) -> Self {
+        Client {
+            subaccount: None,
+            ..self.clone()
+        }
+    }
+
+    pub fn subaccount(&self) -> Option<&str> {
+        self.subaccount.as_deref()
+    }
+
     pub fn get(&self, endpoint: String, request: String) -> Result<String> {
         let mut url: String = format!("{}{}", API_HOST, String::from(endpoint));
         if !request.is_empty() {
@@ -34,67 +59,37 @@ impl Client {
         self.handler(response)
     }
 
-    pub fn get_signed(
-        &self,
-        endpoint: String,
-        payload: String,
-        subaccount: Option<String>,
-    ) -> Result<String> {
+    pub fn get_signed(&self, endpoint: String, payload: String) -> Result<String> {
         let url: String = format!("{}{}", API_HOST, String::from(&endpoint));
         let response = self
             .client
             .get(url.as_str())
-            .headers(self.build_headers(
-                String::from("GET"),
-                endpoint,
-                payload.clone(),
-                subaccount,
-            )?)
+            .headers(self.build_headers(String::from("GET"), endpoint, payload.clone())?)
             .body(payload)
             .send()?;
 
         self.handler(response)
     }
 
-    pub fn post_signed(
-        &self,
-        endpoint: String,
-        payload: String,
-        subaccount: Option<String>,
-    ) -> Result<String> {
+    pub fn post_signed(&self, endpoint: String, payload: String) -> Result<String> {
         let url: String = format!("{}{}", API_HOST, String::from(&endpoint));
         // println!("{:?}", url);
         let response = self
             .client
             .post(url.as_str())
-            .headers(self.build_headers(
-                String::from("POST"),
-                endpoint,
-                payload.clone(),
-                subaccount.clone(),
-            )?)
+            .headers(self.build_headers(String::from("POST"), endpoint, payload.clone())?)
             .body(payload)
             .send()?;
 
         self.handler(response)
     }
 
-    pub fn delete_signed(
-        &self,
-        endpoint: String,
-        payload: String,
-        subaccount: Option<String>,
-    ) -> Result<String> {
+    pub fn delete_signed(&self, endpoint: String, payload: String) -> Result<String> {
         let url: String = format!("{}{}", API_HOST, String::from(&endpoint));
         let response = self
             .client
             .delete(url.as_str())
-            .headers(self.build_headers(
-                String::from("DELETE"),
-                endpoint,
-                payload.clone(),
-                subaccount.clone(),
-            )?)
+            .headers(self.build_headers(String::from("DELETE"), endpoint, payload.clone())?)
             .body(payload)
             .send()?;
 
@@ -106,7 +101,6 @@ impl Client {
         method: String,
         endpoint: String,
         payload: String,
-        subaccount: Option<String>,
     ) -> Result<HeaderMap> {
         let mut headers = HeaderMap::new();
         headers.insert(USER_AGENT, HeaderValue::from_static("ftx-rs"));
@@ -129,11 +123,11 @@ impl Client {
         // set content type
         headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
 
-        // set sub account
-        if let Some(s) = subaccount {
+        // set sub account, the name must be URI-encoded
+        if let Some(ref s) = self.subaccount {
             headers.insert(
                 HeaderName::from_static("ftx-subaccount"),
-                HeaderValue::from_str(s.as_str())?,
+                HeaderValue::from_str(uri_encode(s).as_str())?,
             );
         }
 
@@ -167,3 +161,33 @@ impl Client {
         };
     }
 }
+
+pub(crate) fn uri_encode(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subaccount_header() {
+        let client = Client::new(None, None).for_subaccount("my sub/1");
+        let headers = client
+            .build_headers("GET".into(), "/account".into(), "".into())
+            .unwrap();
+        assert_eq!(headers["ftx-subaccount"], "my%20sub%2F1");
+        let main = Client::new(None, None);
+        let headers = main
+            .build_headers("GET".into(), "/account".into(), "".into())
+            .unwrap();
+        assert!(headers.get("ftx-subaccount").is_none());
+    }
+}
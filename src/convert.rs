@@ -0,0 +1,126 @@
+use crate::client::Client;
+use crate::errors::*;
+use crate::model;
+use crate::timestamps::with_query;
+use crate::wallet::WalletClient;
+use rust_decimal::Decimal;
+
+#[derive(Clone)]
+pub struct ConvertClient {
+    client: Client,
+}
+
+#[derive(Debug, Clone)]
+pub struct DustConversion {
+    pub coin: String,
+    pub size: Decimal,
+    pub usd_value: Decimal,
+    pub quote: Option<model::ConvertQuote>,
+    pub error: Option<String>,
+}
+
+impl ConvertClient {
+    pub fn new(api_key: Option<String>, secret_key: Option<String>) -> Self {
+        ConvertClient {
+            client: Client::new(api_key, secret_key),
+        }
+    }
+
+    pub fn for_subaccount<S>(&self, subaccount: S) -> Self
+    where
+        S: Into<String>,
+    {
+        ConvertClient {
+            client: self.client.for_subaccount(subaccount),
+        }
+    }
+
+    pub fn for_main_account(&self) -> Self {
+        ConvertClient {
+            client: self.client.for_main_account(),
+        }
+    }
+
+    pub fn request_quote(&self, from_coin: String, to_coin: String, size: Decimal) -> Result<i64> {
+        let payload = json!({ "fromCoin": from_coin, "toCoin": to_coin, "size": size });
+        let data = self
+            .client
+            .post_signed("/otc/quotes".into(), payload.to_string())?;
+        let quote: model::ResultData<model::QuoteRequest> = serde_json::from_str(data.as_str())?;
+        Ok(quote.result.quote_id)
+    }
+
+    pub fn get_quote_status(
+        &self,
+        quote_id: i64,
+        market: Option<String>,
+    ) -> Result<model::ConvertQuote> {
+        let payload: String = "{}".into();
+        let params: Vec<(&str, String)> = market.map(|m| ("market", m)).into_iter().collect();
+        let endpoint = with_query(format!("/otc/quotes/{}", quote_id), &params);
+        let data = self.client.get_signed(endpoint, payload)?;
+        let quote: model::ResultData<model::ConvertQuote> = serde_json::from_str(data.as_str())?;
+        Ok(quote.result)
+    }
+
+    pub fn accept_quote(&self, quote_id: i64) -> Result<bool> {
+        let payload: String = "{}".into();
+        let endpoint = format!("/otc/quotes/{}/accept", quote_id);
+        let data = self.client.post_signed(endpoint, payload)?;
+        let r: model::AnotherOption = serde_json::from_str(data.as_str())?;
+        Ok(r.success)
+    }
+
+    // quote, accept and return the filled quote
+    pub fn convert(
+        &self,
+        from_coin: String,
+        to_coin: String,
+        size: Decimal,
+    ) -> Result<model::ConvertQuote> {
+        let quote_id = self.request_quote(from_coin, to_coin, size)?;
+        if !self.accept_quote(quote_id)? {
+            bail!(format!("Quote {} was not accepted", quote_id));
+        }
+        let quote = self.get_quote_status(quote_id, None)?;
+        if !quote.filled {
+            bail!(format!("Quote {} was accepted but not filled", quote_id));
+        }
+        Ok(quote)
+    }
+
+    // Convert every balance worth at least `min_usd_value` into `to_coin`, never
+    // borrowing. `max_usd_value` optionally leaves larger balances alone. A failed
+    // conversion is reported and the sweep carries on with the next coin.
+    pub fn sweep_dust(
+        &self,
+        wallet: &WalletClient,
+        to_coin: String,
+        min_usd_value: Decimal,
+        max_usd_value: Option<Decimal>,
+    ) -> Result<Vec<DustConversion>> {
+        let mut conversions = Vec::new();
+        for balance in wallet.get_balances()? {
+            if balance.coin == to_coin
+                || balance.available_without_borrow <= Decimal::ZERO
+                || balance.usd_value < min_usd_value
+                || max_usd_value.is_some_and(|max| balance.usd_value >= max)
+            {
+                continue;
+            }
+            let size = balance.available_without_borrow;
+            let (quote, error) = match self.convert(balance.coin.clone(), to_coin.clone(), size) {
+                Ok(q) => (Some(q), None),
+                Err(e) => (None, Some(e.to_string())),
+            };
+            conversions.push(DustConversion {
+                coin: balance.coin,
+                size,
+                usd_value: balance.usd_value,
+                quote,
+                error,
+            });
+        }
+        Ok(conversions)
+    }
+}
@@ -3,6 +3,7 @@ use reqwest;
 use url;
 use serde_json;
 use tungstenite;
+use crate::risk::RiskViolation;
 
 error_chain! {
     types {
@@ -14,6 +15,11 @@ error_chain! {
             description("invalid toolchain name")
             display("invalid toolchain name: '{}'", t)
         }
+
+        RiskLimitViolated(v: RiskViolation) {
+            description("risk limit violated")
+            display("risk limit violated: {}", v)
+        }
     }
 
     foreign_links {
@@ -1,3 +1,6 @@
+use crate::model::{Liquidity, OrderStatus, OrderType, PriceLevel, Side};
+use crate::timestamps::float_seconds;
+use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use serde::{self, Deserialize};
 
@@ -44,7 +47,7 @@ pub struct TradeInfo {
     pub size: Decimal,
     pub side: String,
     pub liquidation: bool,
-    pub time: String,
+    pub time: DateTime<Utc>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -59,10 +62,11 @@ pub struct TradesData {
 
 #[derive(Debug, Deserialize)]
 pub struct OrderBookInfo {
-    pub time: Decimal,
+    #[serde(with = "float_seconds")]
+    pub time: DateTime<Utc>,
     pub checksum: i64,
-    pub bids: Vec<Vec<Decimal>>,
-    pub asks: Vec<Vec<Decimal>>,
+    pub bids: Vec<PriceLevel>,
+    pub asks: Vec<PriceLevel>,
     pub action: String,
 }
 
@@ -84,7 +88,8 @@ pub struct TickerInfo {
     pub bid_size: Decimal,
     pub ask_size: Decimal,
     pub last: Decimal,
-    pub time: Decimal,
+    #[serde(with = "float_seconds")]
+    pub time: DateTime<Utc>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -97,21 +102,21 @@ pub struct TickerData {
     pub data: TickerInfo,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct FillInfo {
     pub fee: Decimal,
     pub fee_rate: Decimal,
-    pub future: String,
+    pub future: Option<String>,
     pub id: i64,
-    pub liquidity: String,
+    pub liquidity: Liquidity,
     pub market: String,
     pub order_id: i64,
-    pub trade_id: i64,
+    pub trade_id: Option<i64>,
     pub price: Decimal,
-    pub side: String,
+    pub side: Side,
     pub size: Decimal,
-    pub time: String,
+    pub time: DateTime<Utc>,
     #[serde(rename = "type")]
     pub option_type: String,
 }
@@ -125,24 +130,26 @@ pub struct FillsData {
     pub data: FillInfo,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct OrderInfo {
     pub id: i64,
     pub client_id: Option<String>,
     pub market: String,
     #[serde(rename = "type")]
-    pub option_type: String,
-    pub side: String,
+    pub option_type: OrderType,
+    pub side: Side,
     pub size: Decimal,
-    pub price: Decimal,
+    pub price: Option<Decimal>,
     pub reduce_only: bool,
     pub ioc: bool,
     pub post_only: bool,
-    pub status: String,
+    pub status: OrderStatus,
     pub filled_size: Decimal,
     pub remaining_size: Decimal,
-    pub avg_fill_price: Decimal,
+    pub avg_fill_price: Option<Decimal>,
+    pub liquidation: Option<bool>,
+    pub created_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -0,0 +1,536 @@
+use crate::errors::*;
+use crate::markets::MarketsClient;
+use crate::model::{self, OrderStatus, Side};
+use crate::orders::OrdersClient;
+use ring::rand::{SecureRandom, SystemRandom};
+use rust_decimal::Decimal;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+// longest single sleep, so cancellation is noticed quickly
+const CANCEL_CHECK: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TwapParams {
+    pub market: String,
+    pub side: Side,
+    pub size: Decimal,
+    pub duration: Duration,
+    pub slices: usize,
+    // 0..1, how far slice sizes and gaps may stray from the even schedule
+    pub randomization: Decimal,
+    // None sends market orders, otherwise IOC limit orders at this price
+    pub limit_price: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct IcebergParams {
+    pub market: String,
+    pub side: Side,
+    pub size: Decimal,
+    pub price: Decimal,
+    pub clip_size: Decimal,
+    // 0..1, how far each clip may stray from `clip_size`
+    pub randomization: Decimal,
+    pub post_only: bool,
+    pub poll_interval: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExecutionState {
+    Running,
+    Completed,
+    Cancelled,
+    Failed(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExecutionProgress {
+    pub market: String,
+    pub side: Side,
+    pub size: Decimal,
+    pub filled: Decimal,
+    pub avg_fill_price: Option<Decimal>,
+    pub orders: usize,
+    pub state: ExecutionState,
+}
+
+impl ExecutionProgress {
+    fn new(market: &str, side: Side, size: Decimal) -> Self {
+        ExecutionProgress {
+            market: market.to_string(),
+            side,
+            size,
+            filled: Decimal::ZERO,
+            avg_fill_price: None,
+            orders: 0,
+            state: ExecutionState::Running,
+        }
+    }
+
+    pub fn remaining(&self) -> Decimal {
+        (self.size - self.filled).max(Decimal::ZERO)
+    }
+
+    fn add_fill(&mut self, size: Decimal, price: Option<Decimal>) {
+        if size.is_zero() {
+            return;
+        }
+        let price = match price.or(self.avg_fill_price) {
+            Some(p) => p,
+            None => {
+                self.filled += size;
+                return;
+            }
+        };
+        let notional = self.avg_fill_price.unwrap_or_default() * self.filled + price * size;
+        self.filled += size;
+        self.avg_fill_price = Some(notional / self.filled);
+    }
+}
+
+// A running algorithm. Every fill and state change arrives on `updates`; the
+// last one has a state other than Running.
+pub struct ExecutionHandle {
+    cancel: Arc<AtomicBool>,
+    updates: Receiver<ExecutionProgress>,
+    thread: Option<JoinHandle<ExecutionProgress>>,
+}
+
+impl ExecutionHandle {
+    // stop placing orders and pull whatever is resting
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::SeqCst);
+    }
+
+    pub fn updates(&self) -> &Receiver<ExecutionProgress> {
+        &self.updates
+    }
+
+    // wait for the algorithm to finish and return its final progress
+    pub fn join(mut self) -> Result<ExecutionProgress> {
+        match self.thread.take().map(|t| t.join()) {
+            Some(Ok(progress)) => Ok(progress),
+            _ => bail!("Execution thread panicked"),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Executor {
+    orders: OrdersClient,
+    markets: MarketsClient,
+}
+
+impl Executor {
+    pub fn new(orders: OrdersClient, markets: MarketsClient) -> Self {
+        Executor { orders, markets }
+    }
+
+    // Split `size` into `slices` orders spread over `duration`. Whatever an IOC
+    // slice leaves unfilled is carried into the next one.
+    pub fn twap(&self, params: TwapParams) -> Result<ExecutionHandle> {
+        if params.slices == 0 {
+            bail!("TWAP needs at least one slice");
+        }
+        check_randomization(params.randomization)?;
+        let market = self.markets.get_market(params.market.as_str())?;
+        let size_increment = market.size_increment.unwrap_or_default();
+        let limit_price = match (params.limit_price, market.price_increment) {
+            (Some(p), Some(inc)) => Some(round_price(p, inc, params.side)),
+            (p, _) => p,
+        };
+        let rng = SystemRandom::new();
+        let schedule = twap_schedule(
+            params.size,
+            params.slices,
+            params.randomization,
+            size_increment,
+            || random_unit(&rng),
+        );
+        let interval = params.duration / params.slices as u32;
+        let orders = self.orders.clone();
+
+        Ok(spawn(move |cancel, tx| {
+            let mut progress = ExecutionProgress::new(&params.market, params.side, params.size);
+            let mut planned = Decimal::ZERO;
+            for (i, slice) in schedule.iter().enumerate() {
+                if cancel.load(Ordering::SeqCst) {
+                    progress.state = ExecutionState::Cancelled;
+                    return progress;
+                }
+                planned += *slice;
+                let size = round_size(planned - progress.filled, size_increment);
+                if size > Decimal::ZERO {
+                    let order_type = if limit_price.is_some() {
+                        "limit"
+                    } else {
+                        "market"
+                    };
+                    let sent = orders
+                        .place_order(
+                            params.market.as_str(),
+                            params.side.as_str().to_string(),
+                            limit_price,
+                            order_type.to_string(),
+                            size,
+                            false,
+                            limit_price.is_some(),
+                            false,
+                            None,
+                            false,
+                        )
+                        .and_then(|o| wait_closed(&orders, o));
+                    progress.orders += 1;
+                    match sent {
+                        Ok(order) => progress.add_fill(order.filled_size, order.avg_fill_price),
+                        Err(e) => {
+                            progress.state = ExecutionState::Failed(e.to_string());
+                            return progress;
+                        }
+                    }
+                    let _ = tx.send(progress.clone());
+                }
+                if i + 1 < schedule.len() {
+                    sleep(cancel, scale(interval, jitter(params.randomization, &rng)));
+                }
+            }
+            progress.state = ExecutionState::Completed;
+            progress
+        }))
+    }
+
+    // Show `clip_size` at `price` and replace each clip once it fills, until
+    // `size` is done.
+    pub fn iceberg(&self, params: IcebergParams) -> Result<ExecutionHandle> {
+        if params.clip_size <= Decimal::ZERO {
+            bail!("Iceberg clip size must be positive");
+        }
+        check_randomization(params.randomization)?;
+        let market = self.markets.get_market(params.market.as_str())?;
+        let size_increment = market.size_increment.unwrap_or_default();
+        let price = match market.price_increment {
+            Some(inc) => round_price(params.price, inc, params.side),
+            None => params.price,
+        };
+        let orders = self.orders.clone();
+
+        Ok(spawn(move |cancel, tx| {
+            let rng = SystemRandom::new();
+            let mut progress = ExecutionProgress::new(&params.market, params.side, params.size);
+            loop {
+                let remaining = round_size(progress.remaining(), size_increment);
+                if remaining.is_zero() {
+                    progress.state = ExecutionState::Completed;
+                    return progress;
+                }
+                let clip = round_size(
+                    params.clip_size * jitter(params.randomization, &rng),
+                    size_increment,
+                )
+                .max(size_increment)
+                .min(remaining);
+
+                let order = orders.place_order(
+                    params.market.as_str(),
+                    params.side.as_str().to_string(),
+                    Some(price),
+                    "limit".to_string(),
+                    clip,
+                    false,
+                    false,
+                    params.post_only,
+                    None,
+                    false,
+                );
+                progress.orders += 1;
+                let mut order = match order {
+                    Ok(o) => o,
+                    Err(e) => {
+                        progress.state = ExecutionState::Failed(e.to_string());
+                        return progress;
+                    }
+                };
+
+                // poll the clip, reporting partial fills as they come in
+                let mut clip_fills = ClipFills::default();
+                loop {
+                    if clip_fills.update(&mut progress, &order, price) {
+                        let _ = tx.send(progress.clone());
+                    }
+                    if order.status == OrderStatus::Closed {
+                        break;
+                    }
+                    if cancel.load(Ordering::SeqCst) {
+                        let cancelled = orders.cancel_order(order.id);
+                        // fills that landed since the last poll still count
+                        let last = orders.get_order_status(order.id);
+                        if let Ok(o) = &last {
+                            clip_fills.update(&mut progress, o, price);
+                        }
+                        progress.state = match (cancelled, last) {
+                            (Err(e), _) | (_, Err(e)) => ExecutionState::Failed(e.to_string()),
+                            _ => ExecutionState::Cancelled,
+                        };
+                        return progress;
+                    }
+                    sleep(cancel, params.poll_interval);
+                    order = match orders.get_order_status(order.id) {
+                        Ok(o) => o,
+                        Err(e) => {
+                            progress.state = ExecutionState::Failed(e.to_string());
+                            return progress;
+                        }
+                    };
+                }
+                if order.filled_size < order.size {
+                    progress.state = ExecutionState::Failed(format!(
+                        "clip {} closed with {} of {} filled",
+                        order.id, order.filled_size, order.size
+                    ));
+                    return progress;
+                }
+            }
+        }))
+    }
+}
+
+// what has been booked from one iceberg clip so far
+#[derive(Default)]
+struct ClipFills {
+    size: Decimal,
+    notional: Decimal,
+}
+
+impl ClipFills {
+    // book the clip's fills since the last update; true if there were any
+    fn update(
+        &mut self,
+        progress: &mut ExecutionProgress,
+        order: &model::OrderInfo,
+        price: Decimal,
+    ) -> bool {
+        let filled = order.filled_size - self.size;
+        if filled <= Decimal::ZERO {
+            return false;
+        }
+        let notional = order.avg_fill_price.unwrap_or(price) * order.filled_size;
+        progress.add_fill(filled, Some((notional - self.notional) / filled));
+        self.size = order.filled_size;
+        self.notional = notional;
+        true
+    }
+}
+
+fn spawn<F>(run: F) -> ExecutionHandle
+where
+    F: FnOnce(&AtomicBool, &Sender<ExecutionProgress>) -> ExecutionProgress + Send + 'static,
+{
+    let cancel = Arc::new(AtomicBool::new(false));
+    let (tx, rx) = mpsc::channel();
+    let flag = cancel.clone();
+    let thread = thread::spawn(move || {
+        let progress = run(&flag, &tx);
+        let _ = tx.send(progress.clone());
+        progress
+    });
+    ExecutionHandle {
+        cancel,
+        updates: rx,
+        thread: Some(thread),
+    }
+}
+
+// Market and IOC orders close almost at once, but only the order status has the
+// fill. Polling keeps its pace after a cancel, since the order closes on its own.
+fn wait_closed(orders: &OrdersClient, mut order: model::OrderInfo) -> Result<model::OrderInfo> {
+    while order.status != OrderStatus::Closed {
+        thread::sleep(CANCEL_CHECK);
+        order = orders.get_order_status(order.id)?;
+    }
+    Ok(order)
+}
+
+fn sleep(cancel: &AtomicBool, duration: Duration) {
+    let until = Instant::now() + duration;
+    loop {
+        let now = Instant::now();
+        if now >= until || cancel.load(Ordering::SeqCst) {
+            return;
+        }
+        thread::sleep((until - now).min(CANCEL_CHECK));
+    }
+}
+
+fn scale(duration: Duration, factor: Decimal) -> Duration {
+    let millis = Decimal::from(duration.as_millis() as u64) * factor.max(Decimal::ZERO);
+    Duration::from_millis(u64::try_from(millis.round()).unwrap_or(0))
+}
+
+// uniform in [0, 1)
+fn random_unit(rng: &SystemRandom) -> Decimal {
+    let mut bytes = [0u8; 4];
+    if rng.fill(&mut bytes).is_err() {
+        return Decimal::new(5, 1);
+    }
+    Decimal::from(u32::from_be_bytes(bytes)) / Decimal::from(u64::from(u32::MAX) + 1)
+}
+
+// past 1 a jittered size or gap could come out zero or negative
+fn check_randomization(randomization: Decimal) -> Result<()> {
+    if randomization < Decimal::ZERO || randomization >= Decimal::ONE {
+        bail!(format!(
+            "Randomization must be in [0, 1), got {}",
+            randomization
+        ));
+    }
+    Ok(())
+}
+
+// factor in [1 - randomization, 1 + randomization)
+fn jitter(randomization: Decimal, rng: &SystemRandom) -> Decimal {
+    Decimal::ONE + randomization * (random_unit(rng) * Decimal::TWO - Decimal::ONE)
+}
+
+pub fn round_size(size: Decimal, increment: Decimal) -> Decimal {
+    if increment.is_zero() {
+        return size;
+    }
+    (size / increment).floor() * increment
+}
+
+// round away from the market, so a buy never pays more than asked
+pub fn round_price(price: Decimal, increment: Decimal, side: Side) -> Decimal {
+    if increment.is_zero() {
+        return price;
+    }
+    let steps = price / increment;
+    let steps = match side {
+        Side::Buy => steps.floor(),
+        Side::Sell => steps.ceil(),
+    };
+    steps * increment
+}
+
+// Slice sizes that add up to exactly `size`, each a multiple of `increment`
+// and within `randomization` of an even split. `random` yields values in [0, 1).
+pub fn twap_schedule<R>(
+    size: Decimal,
+    slices: usize,
+    randomization: Decimal,
+    increment: Decimal,
+    mut random: R,
+) -> Vec<Decimal>
+where
+    R: FnMut() -> Decimal,
+{
+    let even = size / Decimal::from(slices as u64);
+    let mut schedule = Vec::with_capacity(slices);
+    let mut left = size;
+    for i in 0..slices {
+        let slice = if i + 1 == slices {
+            left
+        } else {
+            let jitter = Decimal::ONE + randomization * (random() * Decimal::TWO - Decimal::ONE);
+            round_size(even * jitter, increment).min(left)
+        };
+        left -= slice;
+        schedule.push(slice);
+    }
+    schedule
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dec(s: &str) -> Decimal {
+        Decimal::from_str_exact(s).unwrap()
+    }
+
+    #[test]
+    fn test_twap_schedule() {
+        let mut values = [0.0, 0.99, 0.5, 0.25].iter().cycle();
+        let schedule = twap_schedule(dec("10"), 7, dec("0.2"), dec("0.01"), || {
+            Decimal::from_f64_retain(*values.next().unwrap()).unwrap()
+        });
+        assert_eq!(schedule.len(), 7);
+        assert_eq!(schedule.iter().sum::<Decimal>(), dec("10"));
+        assert!(schedule.iter().all(|s| round_size(*s, dec("0.01")) == *s));
+        // 10 / 7 = 1.4285.., less 20%
+        assert_eq!(schedule[0], dec("1.14"));
+    }
+
+    #[test]
+    fn test_rounding() {
+        assert_eq!(round_size(dec("1.239"), dec("0.01")), dec("1.23"));
+        assert_eq!(
+            round_price(dec("100.37"), dec("0.5"), Side::Buy),
+            dec("100.0")
+        );
+        assert_eq!(
+            round_price(dec("100.37"), dec("0.5"), Side::Sell),
+            dec("100.5")
+        );
+    }
+
+    #[test]
+    fn test_iceberg_params() {
+        let executor = Executor::new(OrdersClient::new(None, None), MarketsClient::new());
+        let params = IcebergParams {
+            market: "BTC-PERP".into(),
+            side: Side::Buy,
+            size: dec("10"),
+            price: dec("100"),
+            clip_size: dec("1"),
+            randomization: dec("0.2"),
+            post_only: true,
+            poll_interval: Duration::from_secs(1),
+        };
+        // rejected before anything is sent
+        for (clip_size, randomization) in [("0", "0.2"), ("-1", "0.2"), ("1", "1"), ("1", "-0.1")] {
+            let params = IcebergParams {
+                clip_size: dec(clip_size),
+                randomization: dec(randomization),
+                ..params.clone()
+            };
+            assert!(executor.iceberg(params).is_err());
+        }
+        assert!(check_randomization(dec("0")).is_ok());
+        assert!(check_randomization(dec("0.99")).is_ok());
+    }
+
+    #[test]
+    fn test_progress() {
+        let mut progress = ExecutionProgress::new("BTC-PERP", Side::Buy, dec("3"));
+        progress.add_fill(dec("1"), Some(dec("100")));
+        progress.add_fill(dec("1"), Some(dec("103")));
+        assert_eq!(progress.avg_fill_price, Some(dec("101.5")));
+        assert_eq!(progress.remaining(), dec("1"));
+    }
+
+    #[test]
+    fn test_clip_fills() {
+        let clip = |filled: &str, avg: &str| -> model::OrderInfo {
+            serde_json::from_value(json!({
+                "id": 1, "clientId": null, "market": "BTC-PERP", "type": "limit",
+                "side": "buy", "price": 100, "size": 2, "status": "open",
+                "filledSize": filled, "remainingSize": 0, "reduceOnly": false,
+                "liquidation": null, "avgFillPrice": avg, "postOnly": false, "ioc": false,
+                "createdAt": "2019-03-05T09:56:55.728933+00:00", "future": null
+            }))
+            .unwrap()
+        };
+        let mut progress = ExecutionProgress::new("BTC-PERP", Side::Buy, dec("2"));
+        let mut fills = ClipFills::default();
+        assert!(fills.update(&mut progress, &clip("1", "100"), dec("100")));
+        assert!(!fills.update(&mut progress, &clip("1", "100"), dec("100")));
+        // the status read after a cancel still books what filled in between
+        assert!(fills.update(&mut progress, &clip("2", "99"), dec("100")));
+        assert_eq!(progress.filled, dec("2"));
+        assert_eq!(progress.avg_fill_price, Some(dec("99")));
+    }
+}
@@ -1,6 +1,7 @@
 use crate::client::Client;
 use crate::errors::*;
 use crate::model;
+use crate::timestamps::{with_query, TimeRange};
 
 #[derive(Clone)]
 pub struct FillsClient {
@@ -14,24 +15,31 @@ impl FillsClient {
         }
     }
 
-    pub fn get_fills<S>(
-        &self,
-        symbol: S,
-        start_time: i64,
-        end_time: i64,
-    ) -> Result<Vec<model::FillInfo>>
+    pub fn for_subaccount<S>(&self, subaccount: S) -> Self
     where
-        S: Into<String> + Copy,
+        S: Into<String>,
     {
-        let payload = json!({
-            "market": symbol.into(),
-            "start_time": start_time,
-            "end_time": end_time,
-        });
-        let endpoint = format!("/fills?market={}", symbol.into());
-        let data = self
-            .client
-            .get_signed(endpoint.into(), payload.to_string(), None)?;
+        FillsClient {
+            client: self.client.for_subaccount(subaccount),
+        }
+    }
+
+    pub fn for_main_account(&self) -> Self {
+        FillsClient {
+            client: self.client.for_main_account(),
+        }
+    }
+
+    pub fn get_fills<S, R>(&self, symbol: S, range: R) -> Result<Vec<model::FillInfo>>
+    where
+        S: Into<String>,
+        R: Into<TimeRange>,
+    {
+        let payload: String = "{}".into();
+        let mut params = vec![("market", symbol.into())];
+        params.extend(range.into().params());
+        let endpoint = with_query("/fills".into(), &params);
+        let data = self.client.get_signed(endpoint, payload)?;
         let fills: model::ResultData<Vec<model::FillInfo>> = serde_json::from_str(data.as_str())?;
         Ok(fills.result)
     }
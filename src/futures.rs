@@ -1,6 +1,9 @@
+use crate::candles::{fetch_chunked, Resolution};
 use crate::client::Client;
 use crate::errors::*;
 use crate::model;
+use crate::pagination::paginate;
+use crate::timestamps::{with_query, TimeRange};
 use rust_decimal::Decimal;
 use std::collections::HashMap;
 
@@ -42,12 +45,41 @@ impl FuturesClient {
         Ok(stats.result)
     }
 
-    pub fn get_funding_rates(&self) -> Result<Vec<model::FundingRate>> {
-        let data = self.client.get("/funding_rates".into(), "".into())?;
+    pub fn get_funding_rates<R>(
+        &self,
+        future: Option<String>,
+        range: R,
+    ) -> Result<Vec<model::FundingRate>>
+    where
+        R: Into<TimeRange>,
+    {
+        let mut params = range.into().params();
+        if let Some(f) = future {
+            params.push(("future", f));
+        }
+        let endpoint = with_query("/funding_rates".into(), &params);
+        let data = self.client.get(endpoint, "".into())?;
         let funding_rates: model::FundingRates = serde_json::from_str(data.as_str())?;
         Ok(funding_rates.result)
     }
 
+    // pages through the whole range, newest first
+    pub fn get_all_funding_rates<R>(
+        &self,
+        future: Option<String>,
+        range: R,
+    ) -> Result<Vec<model::FundingRate>>
+    where
+        R: Into<TimeRange>,
+    {
+        paginate(
+            range.into(),
+            |page| self.get_funding_rates(future.clone(), page),
+            |rate| rate.time,
+            |rate| (rate.future.clone(), rate.time),
+        )
+    }
+
     // index_name -> ALT/MID/SHIT/EXCH/DRAGON
     pub fn get_indexes_weights<I>(&self, index_name: I) -> Result<HashMap<String, Decimal>>
     where
@@ -59,6 +91,57 @@ impl FuturesClient {
         Ok(indexes_weights.result)
     }
 
+    pub fn get_index_candles<I, R>(
+        &self,
+        index_name: I,
+        resolution: Resolution,
+        range: R,
+    ) -> Result<Vec<model::CandleInfo>>
+    where
+        I: Into<String>,
+        R: Into<TimeRange>,
+    {
+        let mut params = vec![("resolution", resolution.seconds().to_string())];
+        params.extend(range.into().params());
+        let endpoint = with_query(format!("/indexes/{}/candles", index_name.into()), &params);
+        let data = self.client.get(endpoint, "".into())?;
+        let candles: model::ResultData<Vec<model::CandleInfo>> =
+            serde_json::from_str(data.as_str())?;
+        Ok(candles.result)
+    }
+
+    // splits long ranges into requests under the per-request candle limit
+    pub fn get_all_index_candles<I, R>(
+        &self,
+        index_name: I,
+        resolution: Resolution,
+        range: R,
+    ) -> Result<Vec<model::CandleInfo>>
+    where
+        I: Into<String>,
+        R: Into<TimeRange>,
+    {
+        let index_name: String = index_name.into();
+        fetch_chunked(resolution, range.into(), |chunk| {
+            self.get_index_candles(index_name.as_str(), resolution, chunk)
+        })
+    }
+
+    // index candles of the underlying a future settles against, e.g. BTC for BTC-PERP
+    pub fn get_future_index_candles<S, R>(
+        &self,
+        symbol: S,
+        resolution: Resolution,
+        range: R,
+    ) -> Result<Vec<model::CandleInfo>>
+    where
+        S: Into<String>,
+        R: Into<TimeRange>,
+    {
+        let future = self.get_future(symbol)?;
+        self.get_all_index_candles(future.underlying, resolution, range)
+    }
+
     pub fn get_expired_futures(&self) -> Result<Vec<model::ExpiredFuture>> {
         let data = self.client.get("/expired_futures".into(), "".into())?;
         let expired_futures: model::ExpiredFutures = serde_json::from_str(data.as_str())?;
@@ -101,7 +184,14 @@ mod tests {
     #[test]
     fn test_get_funding_rates() {
         let f = FuturesClient::new();
-        let rates = f.get_funding_rates();
+        let rates = f.get_funding_rates(None, ..);
         println!("{:#?}", rates);
     }
+
+    #[test]
+    fn test_get_index_candles() {
+        let f = FuturesClient::new();
+        let candles = f.get_index_candles("BTC", Resolution::OneHour, ..);
+        println!("{:#?}", candles);
+    }
 }
@@ -0,0 +1,338 @@
+use crate::api::Ftx;
+use crate::errors::*;
+use crate::model;
+use crate::subaccounts::MAIN_ACCOUNT;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::thread;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum KillSwitchStep {
+    ListSubaccounts,
+    ListOrders,
+    CancelOrders,
+    ListPositions,
+    ClosePosition,
+    // orders still open after every market was cancelled
+    VerifyCancelled,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct KillSwitchFailure {
+    pub account: String,
+    pub market: Option<String>,
+    pub step: KillSwitchStep,
+    pub error: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountKillReport {
+    pub name: String,
+    pub cancelled_orders: Vec<model::OrderInfo>,
+    pub cancelled_trigger_orders: Vec<model::TriggerOrderInfo>,
+    // reduce-only market orders sent to flatten positions
+    pub closing_orders: Vec<model::OrderInfo>,
+    pub failures: Vec<KillSwitchFailure>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct KillSwitchReport {
+    pub accounts: Vec<AccountKillReport>,
+    pub failures: Vec<KillSwitchFailure>,
+}
+
+impl KillSwitchReport {
+    pub fn is_clean(&self) -> bool {
+        self.failures.is_empty() && self.accounts.iter().all(|a| a.failures.is_empty())
+    }
+}
+
+impl AccountKillReport {
+    fn fail<E: ToString>(&mut self, market: Option<&str>, step: KillSwitchStep, error: E) {
+        self.failures.push(KillSwitchFailure {
+            account: self.name.clone(),
+            market: market.map(|m| m.to_string()),
+            step,
+            error: error.to_string(),
+        });
+    }
+
+    // credit what `cancel_all_orders` in `market`, or everywhere for None, took down
+    fn record_cancel(
+        &mut self,
+        market: Option<&str>,
+        result: Result<bool>,
+        orders: &[model::OrderInfo],
+        trigger_orders: &[model::TriggerOrderInfo],
+    ) {
+        match result {
+            Ok(true) => {
+                let in_market = |m: &String| market.is_none_or(|market| m == market);
+                self.cancelled_orders
+                    .extend(orders.iter().filter(|o| in_market(&o.market)).cloned());
+                self.cancelled_trigger_orders.extend(
+                    trigger_orders
+                        .iter()
+                        .filter(|o| in_market(&o.market))
+                        .cloned(),
+                );
+            }
+            Ok(false) => self.fail(market, KillSwitchStep::CancelOrders, "rejected"),
+            Err(e) => self.fail(market, KillSwitchStep::CancelOrders, e),
+        }
+    }
+
+    // anything still open once every market was swept is a failure
+    fn record_leftovers(
+        &mut self,
+        orders: Result<Vec<model::OrderInfo>>,
+        trigger_orders: Result<Vec<model::TriggerOrderInfo>>,
+    ) {
+        match orders {
+            Ok(left) => {
+                for order in left {
+                    let error = format!("order {} still open", order.id);
+                    self.fail(Some(&order.market), KillSwitchStep::VerifyCancelled, error);
+                }
+            }
+            Err(e) => self.fail(None, KillSwitchStep::VerifyCancelled, e),
+        }
+        match trigger_orders {
+            Ok(left) => {
+                for order in left {
+                    let error = format!("trigger order {} still open", order.id);
+                    self.fail(Some(&order.market), KillSwitchStep::VerifyCancelled, error);
+                }
+            }
+            Err(e) => self.fail(None, KillSwitchStep::VerifyCancelled, e),
+        }
+    }
+}
+
+// Markets to sweep. Positions are included so stray orders in those markets go too.
+fn kill_markets<'a>(
+    orders: &'a [model::OrderInfo],
+    trigger_orders: &'a [model::TriggerOrderInfo],
+    positions: &'a [model::Position],
+) -> BTreeSet<&'a str> {
+    orders
+        .iter()
+        .map(|o| o.market.as_str())
+        .chain(trigger_orders.iter().map(|o| o.market.as_str()))
+        .chain(
+            positions
+                .iter()
+                .filter(|p| !p.net_size.is_zero())
+                .map(|p| p.future.as_str()),
+        )
+        .collect()
+}
+
+impl Ftx {
+    // Stop trading everywhere: cancel every order, trigger orders included, in
+    // every market of the main account and all subaccounts, then optionally
+    // flatten positions. Errors never stop the sweep; they end up in the report.
+    pub fn kill_switch(&self, close_positions: bool) -> KillSwitchReport {
+        let mut report = KillSwitchReport::default();
+        let mut names = vec![MAIN_ACCOUNT.to_string()];
+        match self.subaccounts.get_subaccounts() {
+            Ok(subaccounts) => names.extend(subaccounts.into_iter().map(|s| s.nickname)),
+            Err(e) => report.failures.push(KillSwitchFailure {
+                account: MAIN_ACCOUNT.to_string(),
+                market: None,
+                step: KillSwitchStep::ListSubaccounts,
+                error: e.to_string(),
+            }),
+        }
+
+        report.accounts = thread::scope(|scope| {
+            let handles: Vec<_> = names
+                .iter()
+                .map(|name| scope.spawn(move || self.kill_account(name, close_positions)))
+                .collect();
+            handles
+                .into_iter()
+                .zip(names.iter())
+                .map(|(h, name)| {
+                    h.join().unwrap_or_else(|_| {
+                        let mut r = AccountKillReport {
+                            name: name.clone(),
+                            ..AccountKillReport::default()
+                        };
+                        r.fail(None, KillSwitchStep::CancelOrders, "worker panicked");
+                        r
+                    })
+                })
+                .collect()
+        });
+        report
+    }
+
+    // `name` is a subaccount nickname or MAIN_ACCOUNT
+    pub fn kill_account(&self, name: &str, close_positions: bool) -> AccountKillReport {
+        let api = self.for_account(name);
+        let mut report = AccountKillReport {
+            name: name.to_string(),
+            ..AccountKillReport::default()
+        };
+
+        let orders = api.orders.get_all_open_orders().unwrap_or_else(|e| {
+            report.fail(None, KillSwitchStep::ListOrders, e);
+            Vec::new()
+        });
+        let trigger_orders = api
+            .orders
+            .get_all_open_trigger_orders()
+            .unwrap_or_else(|e| {
+                report.fail(None, KillSwitchStep::ListOrders, e);
+                Vec::new()
+            });
+        let positions = if close_positions {
+            api.account.get_positions().unwrap_or_else(|e| {
+                report.fail(None, KillSwitchStep::ListPositions, e);
+                Vec::new()
+            })
+        } else {
+            Vec::new()
+        };
+        let positions: Vec<model::Position> = positions
+            .into_iter()
+            .filter(|p| !p.net_size.is_zero())
+            .collect();
+
+        // one account-wide cancel, which works even if the listings above failed;
+        // market by market is only the fallback
+        let result = api.orders.cancel_all_orders(None, None, false, false);
+        let swept = matches!(result, Ok(true));
+        report.record_cancel(None, result, &orders, &trigger_orders);
+        if !swept {
+            for market in kill_markets(&orders, &trigger_orders, &positions) {
+                let result =
+                    api.orders
+                        .cancel_all_orders(Some(market.to_string()), None, false, false);
+                report.record_cancel(Some(market), result, &orders, &trigger_orders);
+            }
+        }
+        report.record_leftovers(
+            api.orders.get_all_open_orders(),
+            api.orders.get_all_open_trigger_orders(),
+        );
+
+        for position in positions {
+            let side = if position.net_size.is_sign_positive() {
+                "sell"
+            } else {
+                "buy"
+            };
+            match api.orders.place_order(
+                position.future.as_str(),
+                side.to_string(),
+                None,
+                "market".to_string(),
+                position.net_size.abs(),
+                true,
+                false,
+                false,
+                None,
+                false,
+            ) {
+                Ok(order) => report.closing_orders.push(order),
+                Err(e) => report.fail(Some(&position.future), KillSwitchStep::ClosePosition, e),
+            }
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order(id: i64, market: &str) -> model::OrderInfo {
+        serde_json::from_value(json!({
+            "id": id, "clientId": null, "market": market, "type": "limit", "side": "buy",
+            "price": 100, "size": 1, "status": "open", "filledSize": 0, "remainingSize": 1,
+            "reduceOnly": false, "liquidation": null, "avgFillPrice": null, "postOnly": false,
+            "ioc": false, "createdAt": "2019-03-05T09:56:55.728933+00:00", "future": null
+        }))
+        .unwrap()
+    }
+
+    fn trigger_order(id: i64, market: &str) -> model::TriggerOrderInfo {
+        serde_json::from_value(json!({
+            "id": id, "market": market, "future": null, "side": "sell", "type": "stop",
+            "orderPrice": null, "triggerPrice": 90, "size": 1, "status": "open",
+            "createdAt": "2019-03-05T09:56:55.728933+00:00", "triggeredAt": null,
+            "orderId": null, "error": null, "reduceOnly": true, "trailValue": null,
+            "trailStart": null, "cancelledAt": null, "cancelReason": null,
+            "retryUntilFilled": false, "orderType": "market", "filledSize": 0,
+            "avgFillPrice": null
+        }))
+        .unwrap()
+    }
+
+    fn position(future: &str, net_size: i64) -> model::Position {
+        serde_json::from_value(json!({
+            "cost": 0, "entryPrice": 100, "future": future,
+            "initialMarginRequirement": 0.1, "longOrderSize": 0,
+            "maintenanceMarginRequirement": 0.03, "netSize": net_size, "openSize": 0,
+            "realizedPnl": 0, "shortOrderSize": 0, "side": "buy",
+            "size": net_size.abs(), "unrealizedPnl": 0
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_kill_markets() {
+        let orders = vec![order(1, "BTC-PERP"), order(2, "BTC-PERP")];
+        let trigger_orders = vec![trigger_order(3, "ETH-PERP")];
+        let positions = vec![position("SOL-PERP", 2), position("DOGE-PERP", 0)];
+        let markets: Vec<&str> = kill_markets(&orders, &trigger_orders, &positions)
+            .into_iter()
+            .collect();
+        assert_eq!(markets, vec!["BTC-PERP", "ETH-PERP", "SOL-PERP"]);
+    }
+
+    #[test]
+    fn test_report() {
+        let orders = vec![order(1, "BTC-PERP"), order(2, "ETH-PERP")];
+        let trigger_orders = vec![trigger_order(3, "BTC-PERP")];
+        let mut report = AccountKillReport {
+            name: "sub".into(),
+            ..AccountKillReport::default()
+        };
+        report.record_cancel(Some("BTC-PERP"), Ok(true), &orders, &trigger_orders);
+        report.record_cancel(Some("ETH-PERP"), Ok(false), &orders, &trigger_orders);
+        assert_eq!(report.cancelled_orders.len(), 1);
+        assert_eq!(report.cancelled_trigger_orders[0].id, 3);
+        assert_eq!(report.failures[0].market.as_deref(), Some("ETH-PERP"));
+        assert_eq!(report.failures[0].step, KillSwitchStep::CancelOrders);
+
+        report.record_leftovers(
+            Ok(vec![order(2, "ETH-PERP")]),
+            Ok(vec![trigger_order(4, "SOL-PERP")]),
+        );
+        assert_eq!(report.failures.len(), 3);
+        assert!(report.failures[1..]
+            .iter()
+            .all(|f| f.step == KillSwitchStep::VerifyCancelled && f.account == "sub"));
+        assert_eq!(report.failures[2].error, "trigger order 4 still open");
+
+        // an account-wide cancel takes everything listed
+        let mut everywhere = AccountKillReport::default();
+        everywhere.record_cancel(None, Ok(true), &orders, &trigger_orders);
+        assert_eq!(everywhere.cancelled_orders.len(), 2);
+        assert_eq!(everywhere.cancelled_trigger_orders.len(), 1);
+        assert!(everywhere.failures.is_empty());
+
+        let full = KillSwitchReport {
+            accounts: vec![report],
+            failures: Vec::new(),
+        };
+        assert!(!full.is_clean());
+    }
+}
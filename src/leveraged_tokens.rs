@@ -0,0 +1,143 @@
+use crate::client::Client;
+use crate::errors::*;
+use crate::model;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone)]
+pub struct LeveragedTokensClient {
+    client: Client,
+}
+
+// underlying position held through a leveraged token balance
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LeveragedTokenExposure {
+    pub token: String,
+    pub underlying: String,
+    pub balance: Decimal,
+    pub position: Decimal,
+    pub usd_value: Option<Decimal>,
+}
+
+impl LeveragedTokensClient {
+    pub fn new(api_key: Option<String>, secret_key: Option<String>) -> Self {
+        LeveragedTokensClient {
+            client: Client::new(api_key, secret_key),
+        }
+    }
+
+    pub fn for_subaccount<S>(&self, subaccount: S) -> Self
+    where
+        S: Into<String>,
+    {
+        LeveragedTokensClient {
+            client: self.client.for_subaccount(subaccount),
+        }
+    }
+
+    pub fn for_main_account(&self) -> Self {
+        LeveragedTokensClient {
+            client: self.client.for_main_account(),
+        }
+    }
+
+    pub fn get_tokens(&self) -> Result<Vec<model::LeveragedToken>> {
+        let data = self.client.get("/lt/tokens".into(), "".into())?;
+        let tokens: model::ResultData<Vec<model::LeveragedToken>> =
+            serde_json::from_str(data.as_str())?;
+        Ok(tokens.result)
+    }
+
+    pub fn get_token<S>(&self, token_name: S) -> Result<model::LeveragedToken>
+    where
+        S: Into<String>,
+    {
+        let endpoint = format!("/lt/{}", token_name.into());
+        let data = self.client.get(endpoint, "".into())?;
+        let token: model::ResultData<model::LeveragedToken> = serde_json::from_str(data.as_str())?;
+        Ok(token.result)
+    }
+
+    pub fn get_balances(&self) -> Result<Vec<model::LeveragedTokenBalance>> {
+        let payload: String = "{}".into();
+        let data = self.client.get_signed("/lt/balances".into(), payload)?;
+        let balances: model::ResultData<Vec<model::LeveragedTokenBalance>> =
+            serde_json::from_str(data.as_str())?;
+        Ok(balances.result)
+    }
+
+    pub fn get_creations(&self) -> Result<Vec<model::LeveragedTokenCreation>> {
+        let payload: String = "{}".into();
+        let data = self.client.get_signed("/lt/creations".into(), payload)?;
+        let creations: model::ResultData<Vec<model::LeveragedTokenCreation>> =
+            serde_json::from_str(data.as_str())?;
+        Ok(creations.result)
+    }
+
+    pub fn request_creation<S>(
+        &self,
+        token_name: S,
+        size: Decimal,
+    ) -> Result<model::LeveragedTokenCreation>
+    where
+        S: Into<String>,
+    {
+        let payload = json!({ "size": size });
+        let endpoint = format!("/lt/{}/create", token_name.into());
+        let data = self.client.post_signed(endpoint, payload.to_string())?;
+        let creation: model::ResultData<model::LeveragedTokenCreation> =
+            serde_json::from_str(data.as_str())?;
+        Ok(creation.result)
+    }
+
+    pub fn get_redemptions(&self) -> Result<Vec<model::LeveragedTokenRedemption>> {
+        let payload: String = "{}".into();
+        let data = self.client.get_signed("/lt/redemptions".into(), payload)?;
+        let redemptions: model::ResultData<Vec<model::LeveragedTokenRedemption>> =
+            serde_json::from_str(data.as_str())?;
+        Ok(redemptions.result)
+    }
+
+    pub fn request_redemption<S>(
+        &self,
+        token_name: S,
+        size: Decimal,
+    ) -> Result<model::LeveragedTokenRedemption>
+    where
+        S: Into<String>,
+    {
+        let payload = json!({ "size": size });
+        let endpoint = format!("/lt/{}/redeem", token_name.into());
+        let data = self.client.post_signed(endpoint, payload.to_string())?;
+        let redemption: model::ResultData<model::LeveragedTokenRedemption> =
+            serde_json::from_str(data.as_str())?;
+        Ok(redemption.result)
+    }
+
+    // non-zero token balances translated into underlying futures exposure
+    pub fn get_exposures(&self) -> Result<Vec<LeveragedTokenExposure>> {
+        let tokens = self.get_tokens()?;
+        let exposures = self
+            .get_balances()?
+            .into_iter()
+            .filter(|b| !b.balance.is_zero())
+            .filter_map(|b| {
+                let token = tokens.iter().find(|t| t.name == b.token)?;
+                Some(exposure(token, b.balance))
+            })
+            .collect();
+        Ok(exposures)
+    }
+}
+
+pub fn exposure(token: &model::LeveragedToken, balance: Decimal) -> LeveragedTokenExposure {
+    let position = balance * token.position_per_share;
+    LeveragedTokenExposure {
+        token: token.name.clone(),
+        underlying: token.underlying.clone(),
+        balance,
+        position,
+        usd_value: token.underlying_mark.map(|mark| position * mark),
+    }
+}
@@ -9,14 +9,33 @@ extern crate serde;
 
 pub mod account;
 pub mod api;
+pub mod basis;
+pub mod bracket;
+pub mod candles;
 pub mod client;
+pub mod convert;
 pub mod errors;
 pub mod events;
+pub mod execution;
 pub mod fills;
 pub mod futures;
+pub mod kill_switch;
+pub mod leveraged_tokens;
+pub mod margin;
 pub mod markets;
 pub mod model;
+pub mod options;
+pub mod orderbook;
 pub mod orders;
+pub mod pagination;
+pub mod pegged;
+pub mod portfolio;
+pub mod rebalance;
+pub mod risk;
+pub mod spot_margin;
+pub mod staking;
 pub mod subaccounts;
+pub mod timestamps;
 pub mod wallet;
+pub mod watchdog;
 pub mod websockets;
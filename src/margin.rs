@@ -0,0 +1,285 @@
+use crate::model::{self, Side};
+use crate::portfolio::AccountSnapshot;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedPosition {
+    pub future: String,
+    pub net_size: Decimal,
+    pub mark: Decimal,
+    pub initial_margin_fraction: Decimal,
+    pub maintenance_margin_fraction: Decimal,
+}
+
+impl SimulatedPosition {
+    pub fn notional(&self) -> Decimal {
+        self.net_size.abs() * self.mark
+    }
+
+    fn maintenance_margin(&self) -> Decimal {
+        self.notional() * self.maintenance_margin_fraction
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PositionProjection {
+    pub future: String,
+    pub net_size: Decimal,
+    pub mark: Decimal,
+    pub notional: Decimal,
+    pub estimated_liquidation_price: Option<Decimal>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MarginProjection {
+    pub collateral: Decimal,
+    pub free_collateral: Decimal,
+    pub position_notional: Decimal,
+    pub initial_margin_requirement: Decimal,
+    pub maintenance_margin_requirement: Decimal,
+    pub margin_fraction: Option<Decimal>,
+    pub positions: Vec<PositionProjection>,
+}
+
+// What-if calculator on top of an account: move marks or add fills and see
+// where margin fraction, free collateral and liquidation prices end up.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MarginSimulator {
+    pub collateral: Decimal,
+    pub positions: Vec<SimulatedPosition>,
+    // used for markets the account holds no position in yet
+    pub initial_margin_fraction: Decimal,
+    pub maintenance_margin_fraction: Decimal,
+}
+
+impl MarginSimulator {
+    pub fn new(
+        collateral: Decimal,
+        initial_margin_fraction: Decimal,
+        maintenance_margin_fraction: Decimal,
+    ) -> Self {
+        MarginSimulator {
+            collateral,
+            positions: Vec::new(),
+            initial_margin_fraction,
+            maintenance_margin_fraction,
+        }
+    }
+
+    pub fn from_account(account: &model::AccountInfo) -> Self {
+        Self::from_positions(account, &account.positions)
+    }
+
+    pub fn from_snapshot(snapshot: &AccountSnapshot) -> Self {
+        Self::from_positions(&snapshot.account, &snapshot.positions)
+    }
+
+    fn from_positions(account: &model::AccountInfo, positions: &[model::Position]) -> Self {
+        let mut sim = MarginSimulator::new(
+            account.collateral,
+            account.initial_margin_requirement,
+            account.maintenance_margin_requirement,
+        );
+        for p in positions.iter().filter(|p| !p.net_size.is_zero()) {
+            sim.positions.push(SimulatedPosition {
+                future: p.future.clone(),
+                net_size: p.net_size,
+                // cost + unrealized pnl is net size times the current mark
+                mark: (p.cost + p.unrealized_pnl) / p.net_size,
+                initial_margin_fraction: p.initial_margin_requirement,
+                maintenance_margin_fraction: p.maintenance_margin_requirement,
+            });
+        }
+        sim
+    }
+
+    // move `future` to `price`, booking the pnl into collateral
+    pub fn set_price<S>(&mut self, future: S, price: Decimal) -> &mut Self
+    where
+        S: Into<String>,
+    {
+        let future: String = future.into();
+        for p in self.positions.iter_mut().filter(|p| p.future == future) {
+            self.collateral += p.net_size * (price - p.mark);
+            p.mark = price;
+        }
+        self
+    }
+
+    // move `future` by `pct`, e.g. -0.1 for a 10% drop
+    pub fn move_price<S>(&mut self, future: S, pct: Decimal) -> &mut Self
+    where
+        S: Into<String>,
+    {
+        let future: String = future.into();
+        let mark = self
+            .positions
+            .iter()
+            .find(|p| p.future == future)
+            .map(|p| p.mark);
+        if let Some(mark) = mark {
+            self.set_price(future, mark * (Decimal::ONE + pct));
+        }
+        self
+    }
+
+    pub fn move_all(&mut self, pct: Decimal) -> &mut Self {
+        for p in self.positions.iter_mut() {
+            let price = p.mark * (Decimal::ONE + pct);
+            self.collateral += p.net_size * (price - p.mark);
+            p.mark = price;
+        }
+        self
+    }
+
+    // treat a hypothetical order as filled at `price`; the gap to the mark is
+    // booked as pnl straight away
+    pub fn fill<S>(&mut self, future: S, side: Side, size: Decimal, price: Decimal) -> &mut Self
+    where
+        S: Into<String>,
+    {
+        let future: String = future.into();
+        let delta = match side {
+            Side::Buy => size,
+            Side::Sell => -size,
+        };
+        let index = match self.positions.iter().position(|p| p.future == future) {
+            Some(i) => i,
+            None => {
+                self.positions.push(SimulatedPosition {
+                    future,
+                    net_size: Decimal::ZERO,
+                    mark: price,
+                    initial_margin_fraction: self.initial_margin_fraction,
+                    maintenance_margin_fraction: self.maintenance_margin_fraction,
+                });
+                self.positions.len() - 1
+            }
+        };
+        let p = &mut self.positions[index];
+        self.collateral += delta * (p.mark - price);
+        p.net_size += delta;
+        self
+    }
+
+    pub fn position_notional(&self) -> Decimal {
+        self.positions.iter().map(|p| p.notional()).sum()
+    }
+
+    pub fn margin_fraction(&self) -> Option<Decimal> {
+        let notional = self.position_notional();
+        if notional.is_zero() {
+            None
+        } else {
+            Some(self.collateral / notional)
+        }
+    }
+
+    // Price of `future` at which collateral falls to the maintenance requirement,
+    // holding every other mark fixed. Solves
+    // collateral + net·(P − mark) = MMR_others + |net|·P·mmf for P.
+    pub fn liquidation_price(&self, future: &str) -> Option<Decimal> {
+        let position = self.positions.iter().find(|p| p.future == future)?;
+        if position.net_size.is_zero() {
+            return None;
+        }
+        let others: Decimal = self
+            .positions
+            .iter()
+            .filter(|p| p.future != future)
+            .map(|p| p.maintenance_margin())
+            .sum();
+        let net = position.net_size;
+        let denominator = net - net.abs() * position.maintenance_margin_fraction;
+        if denominator.is_zero() {
+            return None;
+        }
+        let price = (others - self.collateral + net * position.mark) / denominator;
+        if price > Decimal::ZERO {
+            Some(price)
+        } else {
+            None
+        }
+    }
+
+    pub fn project(&self) -> MarginProjection {
+        let initial: Decimal = self
+            .positions
+            .iter()
+            .map(|p| p.notional() * p.initial_margin_fraction)
+            .sum();
+        let maintenance: Decimal = self.positions.iter().map(|p| p.maintenance_margin()).sum();
+        MarginProjection {
+            collateral: self.collateral,
+            free_collateral: (self.collateral - initial).max(Decimal::ZERO),
+            position_notional: self.position_notional(),
+            initial_margin_requirement: initial,
+            maintenance_margin_requirement: maintenance,
+            margin_fraction: self.margin_fraction(),
+            positions: self
+                .positions
+                .iter()
+                .map(|p| PositionProjection {
+                    future: p.future.clone(),
+                    net_size: p.net_size,
+                    mark: p.mark,
+                    notional: p.notional(),
+                    estimated_liquidation_price: self.liquidation_price(&p.future),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(v: i64) -> Decimal {
+        Decimal::from(v)
+    }
+
+    fn simulator() -> MarginSimulator {
+        let mut sim = MarginSimulator::new(d(1000), Decimal::new(1, 1), Decimal::new(3, 2));
+        sim.fill("BTC-PERP", Side::Buy, d(1), d(10000));
+        sim
+    }
+
+    #[test]
+    fn test_price_move() {
+        let mut sim = simulator();
+        assert_eq!(sim.margin_fraction(), Some(Decimal::new(1, 1)));
+
+        sim.move_price("BTC-PERP", Decimal::new(-5, 2));
+        let projection = sim.project();
+        assert_eq!(projection.collateral, d(500));
+        assert_eq!(projection.position_notional, d(9500));
+        assert_eq!(projection.free_collateral, d(0));
+    }
+
+    #[test]
+    fn test_liquidation_price() {
+        let mut sim = simulator();
+        let price = sim.liquidation_price("BTC-PERP").unwrap();
+        assert_eq!(price.round_dp(2), Decimal::new(927835, 2));
+
+        // at the liquidation price collateral equals the maintenance requirement
+        sim.set_price("BTC-PERP", price);
+        let projection = sim.project();
+        assert_eq!(
+            projection.collateral.round_dp(8),
+            projection.maintenance_margin_requirement.round_dp(8)
+        );
+
+        // a short in another market adds to the requirement and raises the price
+        let mut sim = simulator();
+        sim.fill("ETH-PERP", Side::Sell, d(10), d(1000));
+        assert!(sim.liquidation_price("BTC-PERP").unwrap() > price);
+        assert!(sim.liquidation_price("ETH-PERP").unwrap() > d(1000));
+    }
+}
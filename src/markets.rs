@@ -1,6 +1,8 @@
+use crate::candles::{fetch_chunked, Resolution};
 use crate::client::Client;
 use crate::errors::*;
 use crate::model;
+use crate::timestamps::{with_query, TimeRange};
 
 #[derive(Clone)]
 pub struct MarketsClient {
@@ -57,31 +59,41 @@ impl MarketsClient {
         Ok(trades.result)
     }
 
-    pub fn get_historical_prices<S, R, ST, ET>(
+    pub fn get_historical_prices<S, R>(
         &self,
         symbol: S,
-        resolution: R,
-        start_time: ST,
-        end_time: ET,
+        resolution: Resolution,
+        range: R,
     ) -> Result<Vec<model::CandleInfo>>
     where
         S: Into<String>,
-        R: Into<i64>,
-        ST: Into<i64>,
-        ET: Into<i64>,
+        R: Into<TimeRange>,
     {
-        let endpoint: String = format!(
-            "/markets/{}/candles?resolution={}&start_time={}&end_time={}",
-            symbol.into(),
-            resolution.into(),
-            start_time.into(),
-            end_time.into()
-        );
+        let mut params = vec![("resolution", resolution.seconds().to_string())];
+        params.extend(range.into().params());
+        let endpoint = with_query(format!("/markets/{}/candles", symbol.into()), &params);
         let data = self.client.get(endpoint, "".into())?;
         let candles: model::ResultData<Vec<model::CandleInfo>> =
             serde_json::from_str(data.as_str())?;
         Ok(candles.result)
     }
+
+    // splits long ranges into requests under the per-request candle limit
+    pub fn get_all_historical_prices<S, R>(
+        &self,
+        symbol: S,
+        resolution: Resolution,
+        range: R,
+    ) -> Result<Vec<model::CandleInfo>>
+    where
+        S: Into<String>,
+        R: Into<TimeRange>,
+    {
+        let symbol: String = symbol.into();
+        fetch_chunked(resolution, range.into(), |chunk| {
+            self.get_historical_prices(symbol.as_str(), resolution, chunk)
+        })
+    }
 }
 
 #[cfg(test)]
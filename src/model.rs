@@ -1,3 +1,6 @@
+use crate::events;
+use crate::timestamps::float_seconds;
+use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use serde::{self, Deserialize, Serialize};
 use std::collections::HashMap;
@@ -36,10 +39,29 @@ pub struct MarketInfo {
     pub volume_usd24h: Option<Decimal>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(from = "(Decimal, Decimal)", into = "(Decimal, Decimal)")]
+pub struct PriceLevel {
+    pub price: Decimal,
+    pub size: Decimal,
+}
+
+impl From<(Decimal, Decimal)> for PriceLevel {
+    fn from((price, size): (Decimal, Decimal)) -> Self {
+        PriceLevel { price, size }
+    }
+}
+
+impl From<PriceLevel> for (Decimal, Decimal) {
+    fn from(level: PriceLevel) -> Self {
+        (level.price, level.size)
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Depth {
-    pub bids: Vec<Vec<Decimal>>,
-    pub asks: Vec<Vec<Decimal>>,
+    pub bids: Vec<PriceLevel>,
+    pub asks: Vec<PriceLevel>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -49,13 +71,13 @@ pub struct TradeInfo {
     pub size: Decimal,
     pub side: String,
     pub liquidation: bool,
-    pub time: String,
+    pub time: DateTime<Utc>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct CandleInfo {
-    pub start_time: String,
+    pub start_time: DateTime<Utc>,
     pub time: Decimal,
     pub open: Decimal,
     pub high: Decimal,
@@ -88,8 +110,8 @@ pub struct SubAccountBalanceInfo {
 pub struct TransferInfo {
     pub id: i64,
     pub coin: String,
-    pub size: f64,
-    pub time: String,
+    pub size: Decimal,
+    pub time: DateTime<Utc>,
     pub notes: String,
     pub status: String,
 }
@@ -102,7 +124,7 @@ pub struct Future {
     pub description: String,
     #[serde(rename = "type")]
     pub market_type: String,
-    pub expiry: Option<String>,
+    pub expiry: Option<DateTime<Utc>>,
     pub perpetual: bool,
     pub expired: bool,
     pub enabled: bool,
@@ -119,7 +141,7 @@ pub struct Future {
     pub upper_bound: Option<Decimal>,
     pub underlying_description: String,
     pub expiry_description: String,
-    pub move_start: Option<String>,
+    pub move_start: Option<DateTime<Utc>>,
     pub margin_price: Option<Decimal>,
     pub position_limit_weight: Option<Decimal>,
     pub group: String,
@@ -149,7 +171,7 @@ pub struct FutureOne {
 pub struct StatsInfo {
     pub volume: Option<Decimal>,
     pub next_funding_rate: Option<Decimal>,
-    pub next_funding_time: String,
+    pub next_funding_time: DateTime<Utc>,
     pub expiration_price: Option<Decimal>,
     pub predicted_expiration_price: Option<Decimal>,
     pub strike_price: Option<Decimal>,
@@ -166,7 +188,16 @@ pub struct Stats {
 pub struct FundingRate {
     pub future: String,
     pub rate: Decimal,
-    pub time: String,
+    pub time: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FundingPayment {
+    pub future: String,
+    pub id: i64,
+    pub payment: Decimal,
+    pub time: DateTime<Utc>,
+    pub rate: Option<Decimal>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -189,7 +220,7 @@ pub struct ExpiredFuture {
     pub description: String,
     #[serde(rename = "type")]
     pub marlet_type: String,
-    pub expiry: String,
+    pub expiry: DateTime<Utc>,
     pub perpetual: bool,
     pub expired: bool,
     pub enabled: bool,
@@ -206,7 +237,7 @@ pub struct ExpiredFuture {
     pub upper_bound: Option<Decimal>,
     pub underlying_description: String,
     pub expiry_description: String,
-    pub move_start: Option<String>,
+    pub move_start: Option<DateTime<Utc>>,
     pub margin_price: Option<Decimal>,
     pub position_limit_weight: Option<Decimal>,
     pub group: String,
@@ -219,9 +250,10 @@ pub struct ExpiredFutures {
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
 pub struct Position {
     pub cost: Decimal,
-    pub entry_price: Decimal,
+    pub entry_price: Option<Decimal>,
     pub future: String,
     pub initial_margin_requirement: Decimal,
     pub long_order_size: Decimal,
@@ -230,9 +262,11 @@ pub struct Position {
     pub open_size: Decimal,
     pub realized_pnl: Decimal,
     pub short_order_size: Decimal,
-    pub side: String,
+    pub side: Side,
     pub size: Decimal,
     pub unrealized_pnl: Decimal,
+    pub collateral_used: Option<Decimal>,
+    pub estimated_liquidation_price: Option<Decimal>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -344,9 +378,9 @@ pub struct Deposit {
     pub size: Decimal,
     pub fee: Option<Decimal>,
     pub status: String,
-    pub time: String,
-    pub sent_time: Option<String>,
-    pub confirmed_time: Option<String>,
+    pub time: DateTime<Utc>,
+    pub sent_time: Option<DateTime<Utc>>,
+    pub confirmed_time: Option<DateTime<Utc>>,
     pub confirmations: Option<i64>,
     pub method: Option<String>,
 }
@@ -362,91 +396,880 @@ pub struct Withdrawal {
     pub txid: Option<String>,
     pub size: Decimal,
     pub fee: Option<Decimal>,
-    pub status: String,
-    pub time: String,
+    pub status: WithdrawalStatus,
+    pub time: DateTime<Utc>,
     pub notes: Option<String>,
     pub destination_name: Option<String>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum WithdrawalStatus {
+    Requested,
+    Processing,
+    Sent,
+    Complete,
+    Cancelled,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WithdrawalRequest {
+    pub coin: String,
+    pub size: Decimal,
+    pub address: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+}
+
+impl WithdrawalRequest {
+    pub fn new<C, A>(coin: C, size: Decimal, address: A) -> Self
+    where
+        C: Into<String>,
+        A: Into<String>,
+    {
+        WithdrawalRequest {
+            coin: coin.into(),
+            size,
+            address: address.into(),
+            tag: None,
+            method: None,
+            password: None,
+            code: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WithdrawalFee {
+    pub method: Option<String>,
+    pub address: Option<String>,
+    pub fee: Decimal,
+    pub congested: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedAddress {
+    pub id: i64,
+    pub coin: String,
+    pub address: String,
+    pub tag: Option<String>,
+    pub name: String,
+    pub fav: bool,
+    pub is_prime_trust: bool,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub whitelisted: Option<bool>,
+    pub whitelisted_after: Option<DateTime<Utc>>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Airdrops {
     pub coin: String,
     pub id: i64,
     pub size: Decimal,
-    pub time: String,
+    pub time: DateTime<Utc>,
     pub status: String,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    Buy,
+    Sell,
+}
+
+impl Side {
+    // the string `place_order` expects
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Side::Buy => "buy",
+            Side::Sell => "sell",
+        }
+    }
+
+    pub fn opposite(&self) -> Side {
+        match self {
+            Side::Buy => Side::Sell,
+            Side::Sell => Side::Buy,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum OrderType {
+    Limit,
+    Market,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum OrderStatus {
+    New,
+    Open,
+    Closed,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum TriggerOrderType {
+    Stop,
+    TrailingStop,
+    TakeProfit,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum TriggerOrderStatus {
+    Open,
+    Cancelled,
+    Triggered,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Liquidity {
+    Maker,
+    Taker,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct OrderInfo {
-    id: i64,
-    client_id: Option<String>,
-    market: String,
+    pub id: i64,
+    pub client_id: Option<String>,
+    pub market: String,
     #[serde(rename = "type")]
-    option_type: String,
-    side: String,
-    price: Decimal,
-    size: Decimal,
-    status: String,
-    filled_size: Decimal,
-    remaining_size: Decimal,
-    reduce_only: bool,
-    liquidation: Option<bool>,
-    avg_fill_price: Option<Decimal>,
-    post_only: bool,
-    ioc: bool,
-    created_at: String,
-    future: Option<String>,
+    pub option_type: OrderType,
+    pub side: Side,
+    pub price: Option<Decimal>,
+    pub size: Decimal,
+    pub status: OrderStatus,
+    pub filled_size: Decimal,
+    pub remaining_size: Decimal,
+    pub reduce_only: bool,
+    pub liquidation: Option<bool>,
+    pub avg_fill_price: Option<Decimal>,
+    pub post_only: bool,
+    pub ioc: bool,
+    pub created_at: DateTime<Utc>,
+    pub future: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct TriggerOrderInfo {
-    id: i64,
-    market: String,
-    future: Option<String>,
-    side: String,
+    pub id: i64,
+    pub market: String,
+    pub future: Option<String>,
+    pub side: Side,
     #[serde(rename = "type")]
-    option_type: String,
-    order_price: Option<Decimal>,
-    trigger_price: Decimal,
-    size: Decimal,
-    status: String,
-    created_at: String,
-    triggered_at: Option<String>,
-    order_id: Option<String>,
-    error: Option<String>,
-    reduce_only: bool,
-    trail_value: Option<Decimal>,
-    trail_start: Option<Decimal>,
-    cancelled_at: Option<Decimal>,
-    cancel_reason: Option<String>,
-    retry_until_filled: bool,
-    order_type: String,
-    filled_size: Decimal,
-    avg_fill_price: Option<Decimal>,
+    pub option_type: TriggerOrderType,
+    pub order_price: Option<Decimal>,
+    pub trigger_price: Option<Decimal>,
+    pub size: Decimal,
+    pub status: TriggerOrderStatus,
+    pub created_at: DateTime<Utc>,
+    pub triggered_at: Option<DateTime<Utc>>,
+    pub order_id: Option<i64>,
+    pub error: Option<String>,
+    pub reduce_only: bool,
+    pub trail_value: Option<Decimal>,
+    pub trail_start: Option<Decimal>,
+    pub cancelled_at: Option<DateTime<Utc>>,
+    pub cancel_reason: Option<String>,
+    pub retry_until_filled: bool,
+    pub order_type: OrderType,
+    pub filled_size: Decimal,
+    pub avg_fill_price: Option<Decimal>,
+}
+
+// parameters for `OrdersClient::place_trigger_order`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NewTriggerOrder {
+    pub market: String,
+    pub side: Side,
+    pub size: Decimal,
+    #[serde(rename = "type", serialize_with = "trigger_type_param")]
+    pub option_type: TriggerOrderType,
+    pub reduce_only: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_until_filled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trigger_price: Option<Decimal>,
+    // limit price once triggered, market order when None
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order_price: Option<Decimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trail_value: Option<Decimal>,
+}
+
+impl NewTriggerOrder {
+    pub fn new<M>(market: M, side: Side, option_type: TriggerOrderType, size: Decimal) -> Self
+    where
+        M: Into<String>,
+    {
+        NewTriggerOrder {
+            market: market.into(),
+            side,
+            size,
+            option_type,
+            reduce_only: false,
+            retry_until_filled: None,
+            trigger_price: None,
+            order_price: None,
+            trail_value: None,
+        }
+    }
+}
+
+// orders are placed with camelCase types but listed back in snake_case
+fn trigger_type_param<S>(
+    option_type: &TriggerOrderType,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_str(match option_type {
+        TriggerOrderType::Stop => "stop",
+        TriggerOrderType::TrailingStop => "trailingStop",
+        TriggerOrderType::TakeProfit => "takeProfit",
+    })
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TriggerOrderTrigger {
+    pub time: DateTime<Utc>,
+    pub order_size: Option<Decimal>,
+    pub filled_size: Option<Decimal>,
+    pub order_id: Option<i64>,
+    pub error: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct FillInfo {
-    id: i64,
-    market: String,
-    future: Option<String>,
-    base_currency: String,
-    quote_currency: String,
+    pub id: i64,
+    pub market: String,
+    pub future: Option<String>,
+    pub base_currency: Option<String>,
+    pub quote_currency: Option<String>,
+    #[serde(rename = "type")]
+    pub option_type: String,
+    pub side: Side,
+    pub price: Decimal,
+    pub size: Decimal,
+    pub order_id: i64,
+    pub time: DateTime<Utc>,
+    pub trade_id: Option<i64>,
+    pub fee_rate: Decimal,
+    pub fee: Decimal,
+    pub fee_currency: String,
+    pub liquidity: Liquidity,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SpotMarginRate {
+    pub coin: String,
+    pub estimate: Decimal,
+    pub previous: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BorrowSummary {
+    pub coin: String,
+    pub size: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SpotMarginMarketInfo {
+    pub coin: String,
+    pub borrowed: Decimal,
+    pub free: Decimal,
+    pub estimated_rate: Decimal,
+    pub previous_rate: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BorrowHistory {
+    pub coin: String,
+    pub cost: Decimal,
+    pub rate: Decimal,
+    pub size: Decimal,
+    pub time: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LendingHistory {
+    pub coin: String,
+    pub proceeds: Decimal,
+    pub rate: Decimal,
+    pub size: Decimal,
+    pub time: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LendingOffer {
+    pub coin: String,
+    pub rate: Decimal,
+    pub size: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LendingInfo {
+    pub coin: String,
+    pub lendable: Decimal,
+    pub locked: Decimal,
+    pub min_rate: Option<Decimal>,
+    pub offered: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct QuoteRequest {
+    pub quote_id: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ConvertQuote {
+    pub id: i64,
+    pub base_coin: String,
+    pub quote_coin: String,
+    pub from_coin: String,
+    pub to_coin: String,
+    pub side: Side,
+    pub price: Decimal,
+    pub cost: Decimal,
+    pub proceeds: Decimal,
+    pub expired: bool,
+    #[serde(with = "float_seconds")]
+    pub expiry: DateTime<Utc>,
+    pub filled: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LeveragedToken {
+    pub name: String,
+    pub description: String,
+    pub underlying: String,
+    pub leverage: Decimal,
+    pub outstanding: Decimal,
+    pub price_per_share: Decimal,
+    pub position_per_share: Decimal,
+    pub positions_per_share: Option<HashMap<String, Decimal>>,
+    pub basket: Option<HashMap<String, Decimal>>,
+    pub target_components: Option<Vec<String>>,
+    pub underlying_mark: Option<Decimal>,
+    pub total_nav: Option<Decimal>,
+    pub total_collateral: Option<Decimal>,
+    pub contract_address: Option<String>,
+    pub current_leverage: Option<Decimal>,
+    pub change1h: Option<Decimal>,
+    pub change24h: Option<Decimal>,
+    pub change_bod: Option<Decimal>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LeveragedTokenBalance {
+    pub token: String,
+    pub balance: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LeveragedTokenCreation {
+    pub id: i64,
+    pub token: String,
+    pub requested_size: Decimal,
+    pub pending: bool,
+    pub created_size: Option<Decimal>,
+    pub price: Option<Decimal>,
+    pub cost: Decimal,
+    pub fee: Option<Decimal>,
+    pub requested_at: DateTime<Utc>,
+    pub fulfilled_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LeveragedTokenRedemption {
+    pub id: i64,
+    pub token: String,
+    pub size: Decimal,
+    pub pending: bool,
+    pub price: Option<Decimal>,
+    pub proceeds: Option<Decimal>,
+    pub projected_proceeds: Option<Decimal>,
+    pub fee: Option<Decimal>,
+    pub requested_at: DateTime<Utc>,
+    pub fulfilled_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum OptionType {
+    Call,
+    Put,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum OptionRequestStatus {
+    Open,
+    Filled,
+    Cancelled,
+    Expired,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct OptionContract {
+    pub underlying: String,
+    #[serde(rename = "type")]
+    pub option_type: OptionType,
+    pub strike: Decimal,
+    pub expiry: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OptionQuoteRequest {
+    pub id: i64,
+    pub option: OptionContract,
+    pub side: Side,
+    pub size: Decimal,
+    pub time: DateTime<Utc>,
+    pub request_expiry: Option<DateTime<Utc>>,
+    pub status: OptionRequestStatus,
+    pub limit_price: Option<Decimal>,
+    pub hide_limit_price: Option<bool>,
+    pub quotes: Option<Vec<OptionQuote>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OptionQuote {
+    pub id: i64,
+    pub request_id: i64,
+    pub option: Option<OptionContract>,
+    pub price: Decimal,
+    pub size: Decimal,
+    pub collateral: Option<Decimal>,
+    pub quoter_side: Option<Side>,
+    pub request_side: Option<Side>,
+    pub quote_expiry: Option<DateTime<Utc>>,
+    pub status: OptionRequestStatus,
+    pub time: DateTime<Utc>,
+}
+
+// parameters for `OptionsClient::create_quote_request`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NewOptionQuoteRequest {
+    pub underlying: String,
     #[serde(rename = "type")]
-    option_type: String,
-    side: String,
-    price: Decimal,
-    size: Decimal,
-    order_id: i64,
-    time: String,
-    trade_id: i64,
-    fee_rate: Decimal,
-    fee: Decimal,
-    fee_currency: String,
-    liquidity: String,
+    pub option_type: OptionType,
+    pub strike: Decimal,
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub expiry: DateTime<Utc>,
+    pub side: Side,
+    pub size: Decimal,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit_price: Option<Decimal>,
+    pub hide_limit_price: bool,
+    #[serde(
+        with = "chrono::serde::ts_seconds_option",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    pub request_expiry: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub counterparty_id: Option<i64>,
+}
+
+impl NewOptionQuoteRequest {
+    pub fn new<U>(
+        underlying: U,
+        option_type: OptionType,
+        strike: Decimal,
+        expiry: DateTime<Utc>,
+        side: Side,
+        size: Decimal,
+    ) -> Self
+    where
+        U: Into<String>,
+    {
+        NewOptionQuoteRequest {
+            underlying: underlying.into(),
+            option_type,
+            strike,
+            expiry,
+            side,
+            size,
+            limit_price: None,
+            hide_limit_price: true,
+            request_expiry: None,
+            counterparty_id: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OptionsAccountInfo {
+    pub usd_balance: Decimal,
+    pub liquidation_price: Option<Decimal>,
+    pub liquidating: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OptionPosition {
+    pub option: OptionContract,
+    pub side: Side,
+    pub size: Decimal,
+    pub net_size: Decimal,
+    pub entry_price: Decimal,
+    pub pessimistic_valuation: Option<Decimal>,
+    pub pessimistic_index_price: Option<Decimal>,
+    pub pessimistic_vol: Option<Decimal>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OptionTrade {
+    pub id: i64,
+    pub option: OptionContract,
+    pub price: Decimal,
+    pub size: Decimal,
+    pub time: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OptionFill {
+    pub id: i64,
+    pub option: OptionContract,
+    pub quote_id: i64,
+    pub side: Side,
+    pub price: Decimal,
+    pub size: Decimal,
+    pub fee: Decimal,
+    pub fee_rate: Decimal,
+    pub liquidity: Liquidity,
+    pub time: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Stake {
+    pub id: i64,
+    pub coin: String,
+    pub size: Decimal,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UnstakeRequest {
+    pub id: i64,
+    pub coin: String,
+    pub size: Decimal,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub unlock_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StakeBalance {
+    pub coin: String,
+    pub lifetime_rewards: Decimal,
+    pub scheduled_to_unstake: Decimal,
+    pub staked: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StakingReward {
+    pub id: i64,
+    pub coin: String,
+    pub size: Decimal,
+    pub status: String,
+    pub time: DateTime<Utc>,
+}
+
+/// Fill shared by the REST `/fills` endpoint and the `fills` websocket channel.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Fill {
+    pub id: i64,
+    pub market: String,
+    pub future: Option<String>,
+    pub base_currency: Option<String>,
+    pub quote_currency: Option<String>,
+    #[serde(rename = "type")]
+    pub option_type: String,
+    pub side: Side,
+    pub price: Decimal,
+    pub size: Decimal,
+    pub order_id: i64,
+    pub trade_id: Option<i64>,
+    pub fee: Decimal,
+    pub fee_rate: Decimal,
+    pub fee_currency: Option<String>,
+    pub liquidity: Liquidity,
+    pub time: DateTime<Utc>,
+}
+
+impl From<FillInfo> for Fill {
+    fn from(f: FillInfo) -> Self {
+        Fill {
+            id: f.id,
+            market: f.market,
+            future: f.future,
+            base_currency: f.base_currency,
+            quote_currency: f.quote_currency,
+            option_type: f.option_type,
+            side: f.side,
+            price: f.price,
+            size: f.size,
+            order_id: f.order_id,
+            trade_id: f.trade_id,
+            fee: f.fee,
+            fee_rate: f.fee_rate,
+            fee_currency: Some(f.fee_currency),
+            liquidity: f.liquidity,
+            time: f.time,
+        }
+    }
+}
+
+impl From<events::FillInfo> for Fill {
+    fn from(f: events::FillInfo) -> Self {
+        Fill {
+            id: f.id,
+            market: f.market,
+            future: f.future,
+            base_currency: None,
+            quote_currency: None,
+            option_type: f.option_type,
+            side: f.side,
+            price: f.price,
+            size: f.size,
+            order_id: f.order_id,
+            trade_id: f.trade_id,
+            fee: f.fee,
+            fee_rate: f.fee_rate,
+            fee_currency: None,
+            liquidity: f.liquidity,
+            time: f.time,
+        }
+    }
+}
+
+/// Order shared by the REST order endpoints and the `orders` websocket channel.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Order {
+    pub id: i64,
+    pub client_id: Option<String>,
+    pub market: String,
+    pub future: Option<String>,
+    #[serde(rename = "type")]
+    pub option_type: OrderType,
+    pub side: Side,
+    pub price: Option<Decimal>,
+    pub size: Decimal,
+    pub status: OrderStatus,
+    pub filled_size: Decimal,
+    pub remaining_size: Decimal,
+    pub avg_fill_price: Option<Decimal>,
+    pub reduce_only: bool,
+    pub ioc: bool,
+    pub post_only: bool,
+    pub liquidation: Option<bool>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl From<OrderInfo> for Order {
+    fn from(o: OrderInfo) -> Self {
+        Order {
+            id: o.id,
+            client_id: o.client_id,
+            market: o.market,
+            future: o.future,
+            option_type: o.option_type,
+            side: o.side,
+            price: o.price,
+            size: o.size,
+            status: o.status,
+            filled_size: o.filled_size,
+            remaining_size: o.remaining_size,
+            avg_fill_price: o.avg_fill_price,
+            reduce_only: o.reduce_only,
+            ioc: o.ioc,
+            post_only: o.post_only,
+            liquidation: o.liquidation,
+            created_at: Some(o.created_at),
+        }
+    }
+}
+
+impl From<events::OrderInfo> for Order {
+    fn from(o: events::OrderInfo) -> Self {
+        Order {
+            id: o.id,
+            client_id: o.client_id,
+            market: o.market,
+            future: None,
+            option_type: o.option_type,
+            side: o.side,
+            price: o.price,
+            size: o.size,
+            status: o.status,
+            filled_size: o.filled_size,
+            remaining_size: o.remaining_size,
+            avg_fill_price: o.avg_fill_price,
+            reduce_only: o.reduce_only,
+            ioc: o.ioc,
+            post_only: o.post_only,
+            liquidation: o.liquidation,
+            created_at: o.created_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_order_info() {
+        let data = r#"{
+            "createdAt": "2019-03-05T09:56:55.728933+00:00",
+            "filledSize": 0,
+            "future": "XRP-PERP",
+            "id": 9596912,
+            "market": "XRP-PERP",
+            "price": 0.306525,
+            "avgFillPrice": null,
+            "remainingSize": 31431,
+            "side": "sell",
+            "size": 31431,
+            "status": "open",
+            "type": "limit",
+            "reduceOnly": false,
+            "ioc": false,
+            "postOnly": false,
+            "clientId": null
+        }"#;
+        let order: OrderInfo = serde_json::from_str(data).unwrap();
+        assert_eq!(order.side, Side::Sell);
+        assert_eq!(order.status, OrderStatus::Open);
+        assert_eq!(order.option_type, OrderType::Limit);
+        assert_eq!(order.created_at.timestamp(), 1551779815);
+    }
+
+    #[test]
+    fn test_unified_fill() {
+        let rest = r#"{
+            "fee": 20.1374935,
+            "feeCurrency": "USD",
+            "feeRate": 0.0005,
+            "future": "EOS-0329",
+            "id": 11215,
+            "liquidity": "taker",
+            "market": "EOS-0329",
+            "baseCurrency": null,
+            "quoteCurrency": null,
+            "orderId": 8436981,
+            "tradeId": 1013912,
+            "price": 4.201,
+            "side": "buy",
+            "size": 9587,
+            "time": "2019-03-27T19:15:10.204619+00:00",
+            "type": "order"
+        }"#;
+        let ws = r#"{
+            "fee": 20.1374935,
+            "feeRate": 0.0005,
+            "future": "EOS-0329",
+            "id": 11215,
+            "liquidity": "taker",
+            "market": "EOS-0329",
+            "orderId": 8436981,
+            "tradeId": 1013912,
+            "price": 4.201,
+            "side": "buy",
+            "size": 9587,
+            "time": "2019-03-27T19:15:10.204619+00:00",
+            "type": "order"
+        }"#;
+        let from_rest: Fill = serde_json::from_str::<FillInfo>(rest).unwrap().into();
+        let from_ws: Fill = serde_json::from_str::<events::FillInfo>(ws).unwrap().into();
+        assert_eq!(from_rest.id, from_ws.id);
+        assert_eq!(from_rest.time, from_ws.time);
+        assert_eq!(from_rest.fee_currency.as_deref(), Some("USD"));
+        assert_eq!(from_ws.fee_currency, None);
+    }
+
+    #[test]
+    fn test_withdrawal_request_payload() {
+        let mut request = WithdrawalRequest::new("USDC", Decimal::from(10), "0x83a1");
+        request.code = Some("152823".into());
+        let payload = serde_json::to_value(&request).unwrap();
+        assert_eq!(payload["code"], "152823");
+        assert!(payload.get("tag").is_none());
+        assert!(payload.get("password").is_none());
+    }
+
+    #[test]
+    fn test_trigger_order_payload() {
+        let mut order = NewTriggerOrder::new(
+            "BTC-PERP",
+            Side::Sell,
+            TriggerOrderType::TakeProfit,
+            Decimal::ONE,
+        );
+        order.reduce_only = true;
+        order.trigger_price = Some(Decimal::from(50000));
+        assert_eq!(
+            serde_json::to_value(&order).unwrap(),
+            json!({
+                "market": "BTC-PERP", "side": "sell", "size": "1", "type": "takeProfit",
+                "reduceOnly": true, "triggerPrice": "50000"
+            })
+        );
+    }
+
+    #[test]
+    fn test_option_quote_request_payload() {
+        let expiry = crate::timestamps::from_seconds(1590105600);
+        let request = NewOptionQuoteRequest::new(
+            "BTC",
+            OptionType::Call,
+            Decimal::from(9000),
+            expiry,
+            Side::Buy,
+            Decimal::from(1),
+        );
+        let payload = serde_json::to_value(&request).unwrap();
+        assert_eq!(payload["type"], "call");
+        assert_eq!(payload["expiry"], 1590105600);
+        assert!(payload.get("requestExpiry").is_none());
+    }
 }
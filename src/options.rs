@@ -0,0 +1,161 @@
+use crate::client::Client;
+use crate::errors::*;
+use crate::model;
+use crate::timestamps::{with_query, TimeRange};
+use rust_decimal::Decimal;
+
+#[derive(Clone)]
+pub struct OptionsClient {
+    client: Client,
+}
+
+impl OptionsClient {
+    pub fn new(api_key: Option<String>, secret_key: Option<String>) -> Self {
+        OptionsClient {
+            client: Client::new(api_key, secret_key),
+        }
+    }
+
+    pub fn for_subaccount<S>(&self, subaccount: S) -> Self
+    where
+        S: Into<String>,
+    {
+        OptionsClient {
+            client: self.client.for_subaccount(subaccount),
+        }
+    }
+
+    pub fn for_main_account(&self) -> Self {
+        OptionsClient {
+            client: self.client.for_main_account(),
+        }
+    }
+
+    // every open quote request on the exchange
+    pub fn get_quote_requests(&self) -> Result<Vec<model::OptionQuoteRequest>> {
+        let data = self.client.get("/options/requests".into(), "".into())?;
+        let requests: model::ResultData<Vec<model::OptionQuoteRequest>> =
+            serde_json::from_str(data.as_str())?;
+        Ok(requests.result)
+    }
+
+    pub fn get_my_quote_requests(&self) -> Result<Vec<model::OptionQuoteRequest>> {
+        let payload: String = "{}".into();
+        let data = self
+            .client
+            .get_signed("/options/my_requests".into(), payload)?;
+        let requests: model::ResultData<Vec<model::OptionQuoteRequest>> =
+            serde_json::from_str(data.as_str())?;
+        Ok(requests.result)
+    }
+
+    pub fn create_quote_request(
+        &self,
+        request: &model::NewOptionQuoteRequest,
+    ) -> Result<model::OptionQuoteRequest> {
+        let payload = serde_json::to_string(request)?;
+        let data = self
+            .client
+            .post_signed("/options/requests".into(), payload)?;
+        let created: model::ResultData<model::OptionQuoteRequest> =
+            serde_json::from_str(data.as_str())?;
+        Ok(created.result)
+    }
+
+    pub fn cancel_quote_request(&self, request_id: i64) -> Result<model::OptionQuoteRequest> {
+        let payload: String = "{}".into();
+        let endpoint = format!("/options/requests/{}", request_id);
+        let data = self.client.delete_signed(endpoint, payload)?;
+        let cancelled: model::ResultData<model::OptionQuoteRequest> =
+            serde_json::from_str(data.as_str())?;
+        Ok(cancelled.result)
+    }
+
+    // quotes received for one of my requests
+    pub fn get_quotes_for_request(&self, request_id: i64) -> Result<Vec<model::OptionQuote>> {
+        let payload: String = "{}".into();
+        let endpoint = format!("/options/requests/{}/quotes", request_id);
+        let data = self.client.get_signed(endpoint, payload)?;
+        let quotes: model::ResultData<Vec<model::OptionQuote>> =
+            serde_json::from_str(data.as_str())?;
+        Ok(quotes.result)
+    }
+
+    pub fn create_quote(&self, request_id: i64, price: Decimal) -> Result<model::OptionQuote> {
+        let payload = json!({ "price": price });
+        let endpoint = format!("/options/requests/{}/quotes", request_id);
+        let data = self.client.post_signed(endpoint, payload.to_string())?;
+        let quote: model::ResultData<model::OptionQuote> = serde_json::from_str(data.as_str())?;
+        Ok(quote.result)
+    }
+
+    // quotes I have sent to other users' requests
+    pub fn get_my_quotes(&self) -> Result<Vec<model::OptionQuote>> {
+        let payload: String = "{}".into();
+        let data = self
+            .client
+            .get_signed("/options/my_quotes".into(), payload)?;
+        let quotes: model::ResultData<Vec<model::OptionQuote>> =
+            serde_json::from_str(data.as_str())?;
+        Ok(quotes.result)
+    }
+
+    pub fn cancel_quote(&self, quote_id: i64) -> Result<model::OptionQuote> {
+        let payload: String = "{}".into();
+        let endpoint = format!("/options/quotes/{}", quote_id);
+        let data = self.client.delete_signed(endpoint, payload)?;
+        let quote: model::ResultData<model::OptionQuote> = serde_json::from_str(data.as_str())?;
+        Ok(quote.result)
+    }
+
+    pub fn accept_quote(&self, quote_id: i64) -> Result<model::OptionQuote> {
+        let payload: String = "{}".into();
+        let endpoint = format!("/options/quotes/{}/accept", quote_id);
+        let data = self.client.post_signed(endpoint, payload)?;
+        let quote: model::ResultData<model::OptionQuote> = serde_json::from_str(data.as_str())?;
+        Ok(quote.result)
+    }
+
+    pub fn get_account_info(&self) -> Result<model::OptionsAccountInfo> {
+        let payload: String = "{}".into();
+        let data = self
+            .client
+            .get_signed("/options/account_info".into(), payload)?;
+        let info: model::ResultData<model::OptionsAccountInfo> =
+            serde_json::from_str(data.as_str())?;
+        Ok(info.result)
+    }
+
+    pub fn get_positions(&self) -> Result<Vec<model::OptionPosition>> {
+        let payload: String = "{}".into();
+        let data = self
+            .client
+            .get_signed("/options/positions".into(), payload)?;
+        let positions: model::ResultData<Vec<model::OptionPosition>> =
+            serde_json::from_str(data.as_str())?;
+        Ok(positions.result)
+    }
+
+    // public trades across all options
+    pub fn get_trades<R>(&self, range: R) -> Result<Vec<model::OptionTrade>>
+    where
+        R: Into<TimeRange>,
+    {
+        let endpoint = with_query("/options/trades".into(), &range.into().params());
+        let data = self.client.get(endpoint, "".into())?;
+        let trades: model::ResultData<Vec<model::OptionTrade>> =
+            serde_json::from_str(data.as_str())?;
+        Ok(trades.result)
+    }
+
+    pub fn get_fills<R>(&self, range: R) -> Result<Vec<model::OptionFill>>
+    where
+        R: Into<TimeRange>,
+    {
+        let payload: String = "{}".into();
+        let endpoint = with_query("/options/fills".into(), &range.into().params());
+        let data = self.client.get_signed(endpoint, payload)?;
+        let fills: model::ResultData<Vec<model::OptionFill>> = serde_json::from_str(data.as_str())?;
+        Ok(fills.result)
+    }
+}
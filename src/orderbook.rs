@@ -0,0 +1,166 @@
+use crate::model::{Depth, PriceLevel, Side};
+use rust_decimal::Decimal;
+
+// Helpers on an order book snapshot. `side` is always the side of the order
+// walking the book: a `Buy` consumes asks, a `Sell` consumes bids.
+impl Depth {
+    pub fn best_bid(&self) -> Option<PriceLevel> {
+        self.bids.first().copied()
+    }
+
+    pub fn best_ask(&self) -> Option<PriceLevel> {
+        self.asks.first().copied()
+    }
+
+    pub fn mid_price(&self) -> Option<Decimal> {
+        let bid = self.best_bid()?;
+        let ask = self.best_ask()?;
+        Some((bid.price + ask.price) / Decimal::from(2))
+    }
+
+    pub fn spread(&self) -> Option<Decimal> {
+        Some(self.best_ask()?.price - self.best_bid()?.price)
+    }
+
+    pub fn spread_bps(&self) -> Option<Decimal> {
+        let mid = self.mid_price()?;
+        if mid.is_zero() {
+            return None;
+        }
+        Some(self.spread()? / mid * Decimal::from(10_000))
+    }
+
+    fn levels(&self, side: Side) -> &[PriceLevel] {
+        match side {
+            Side::Buy => &self.asks,
+            Side::Sell => &self.bids,
+        }
+    }
+
+    // total size available to `side` at `price` or better
+    pub fn cumulative_depth(&self, side: Side, price: Decimal) -> Decimal {
+        self.levels(side)
+            .iter()
+            .take_while(|l| match side {
+                Side::Buy => l.price <= price,
+                Side::Sell => l.price >= price,
+            })
+            .map(|l| l.size)
+            .sum()
+    }
+
+    // average fill price for a market order of `size`, None if the book is too thin
+    pub fn vwap(&self, side: Side, size: Decimal) -> Option<Decimal> {
+        if size <= Decimal::ZERO {
+            return None;
+        }
+        let mut remaining = size;
+        let mut notional = Decimal::ZERO;
+        for level in self.levels(side) {
+            let take = remaining.min(level.size);
+            notional += take * level.price;
+            remaining -= take;
+            if remaining.is_zero() {
+                return Some(notional / size);
+            }
+        }
+        None
+    }
+
+    // cost of a market order of `size` relative to the mid price, in bps
+    pub fn slippage_bps(&self, side: Side, size: Decimal) -> Option<Decimal> {
+        let mid = self.mid_price()?;
+        if mid.is_zero() {
+            return None;
+        }
+        let vwap = self.vwap(side, size)?;
+        let diff = match side {
+            Side::Buy => vwap - mid,
+            Side::Sell => mid - vwap,
+        };
+        Some(diff / mid * Decimal::from(10_000))
+    }
+
+    fn top_size(levels: &[PriceLevel], n: usize) -> Decimal {
+        levels.iter().take(n).map(|l| l.size).sum()
+    }
+
+    // (bid size - ask size) / (bid size + ask size) over the top `levels`, in [-1, 1]
+    pub fn imbalance(&self, levels: usize) -> Option<Decimal> {
+        let bids = Self::top_size(&self.bids, levels);
+        let asks = Self::top_size(&self.asks, levels);
+        let total = bids + asks;
+        if total.is_zero() {
+            return None;
+        }
+        Some((bids - asks) / total)
+    }
+
+    // bid size / ask size over the top `levels`
+    pub fn bid_ask_ratio(&self, levels: usize) -> Option<Decimal> {
+        let asks = Self::top_size(&self.asks, levels);
+        if asks.is_zero() {
+            return None;
+        }
+        Some(Self::top_size(&self.bids, levels) / asks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn book() -> Depth {
+        serde_json::from_str(
+            r#"{
+                "bids": [[99.0, 1.0], [98.0, 2.0], [97.0, 3.0]],
+                "asks": [[101.0, 1.0], [102.0, 1.0], [103.0, 4.0]]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_top_of_book() {
+        let b = book();
+        assert_eq!(b.best_bid().unwrap().price, Decimal::from(99));
+        assert_eq!(b.best_ask().unwrap().size, Decimal::from(1));
+        assert_eq!(b.mid_price(), Some(Decimal::from(100)));
+        assert_eq!(b.spread_bps(), Some(Decimal::from(200)));
+    }
+
+    #[test]
+    fn test_walk_book() {
+        let b = book();
+        assert_eq!(
+            b.cumulative_depth(Side::Buy, Decimal::from(102)),
+            Decimal::from(2)
+        );
+        assert_eq!(
+            b.cumulative_depth(Side::Sell, Decimal::from(97)),
+            Decimal::from(6)
+        );
+        assert_eq!(
+            b.vwap(Side::Buy, Decimal::from(2)),
+            Decimal::from_str_exact("101.5").ok()
+        );
+        assert_eq!(b.vwap(Side::Sell, Decimal::from(7)), None);
+        assert_eq!(
+            b.slippage_bps(Side::Buy, Decimal::from(2)),
+            Some(Decimal::from(150))
+        );
+    }
+
+    #[test]
+    fn test_imbalance() {
+        let b = book();
+        assert_eq!(
+            b.imbalance(2),
+            Some(Decimal::from_str_exact("0.2").unwrap())
+        );
+        assert_eq!(
+            b.bid_ask_ratio(2),
+            Some(Decimal::from_str_exact("1.5").unwrap())
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use crate::client::Client;
 use crate::errors::*;
 use crate::model;
+use crate::timestamps::{with_query, TimeRange};
 use rust_decimal::Decimal;
 
 #[derive(Clone)]
@@ -15,44 +16,78 @@ impl OrdersClient {
         }
     }
 
+    pub fn for_subaccount<S>(&self, subaccount: S) -> Self
+    where
+        S: Into<String>,
+    {
+        OrdersClient {
+            client: self.client.for_subaccount(subaccount),
+        }
+    }
+
+    pub fn for_main_account(&self) -> Self {
+        OrdersClient {
+            client: self.client.for_main_account(),
+        }
+    }
+
     pub fn get_open_orders<S>(&self, symbol: S) -> Result<Vec<model::OrderInfo>>
     where
         S: Into<String>,
     {
         let payload: String = format!("{}", "{}");
-        let endpoint = format!("/orders?market={}", symbol.into());
-        let data = self.client.get_signed(endpoint.into(), payload, None)?;
+        let endpoint = with_query("/orders".into(), &[("market", symbol.into())]);
+        let data = self.client.get_signed(endpoint.into(), payload)?;
 
         let open_orders: model::ResultData<Vec<model::OrderInfo>> =
             serde_json::from_str(data.as_str())?;
         Ok(open_orders.result)
     }
 
-    pub fn get_order_history<S>(
+    // open orders across every market
+    pub fn get_all_open_orders(&self) -> Result<Vec<model::OrderInfo>> {
+        let payload: String = "{}".into();
+        let data = self.client.get_signed("/orders".into(), payload)?;
+        let open_orders: model::ResultData<Vec<model::OrderInfo>> =
+            serde_json::from_str(data.as_str())?;
+        Ok(open_orders.result)
+    }
+
+    pub fn get_order_history<S, R>(
         &self,
         symbol: S,
         side: Option<String>,
         order_type: String,
-        start_time: i64,
-        end_time: i64,
+        range: R,
     ) -> Result<model::ResultData<Vec<model::OrderInfo>>>
     where
         S: Into<String>,
+        R: Into<TimeRange>,
     {
-        let mut payload =
-            json!({ "orderType": order_type, "start_time": start_time, "end_time": end_time });
+        let payload: String = "{}".into();
+        let mut params = vec![("market", symbol.into()), ("orderType", order_type)];
         if let Some(s) = side {
-            payload = json!({ "side": s, "orderType": order_type, "start_time": start_time, "end_time": end_time });
+            params.push(("side", s));
         }
-        let endpoint = format!("/orders/history?market={}", symbol.into());
-        let data = self
-            .client
-            .get_signed(endpoint.into(), payload.to_string(), None)?;
+        params.extend(range.into().params());
+        let endpoint = with_query("/orders/history".into(), &params);
+        let data = self.client.get_signed(endpoint, payload)?;
         let history_orders: model::ResultData<Vec<model::OrderInfo>> =
             serde_json::from_str(data.as_str())?;
         Ok(history_orders)
     }
 
+    // open trigger orders across every market
+    pub fn get_all_open_trigger_orders(&self) -> Result<Vec<model::TriggerOrderInfo>> {
+        let payload: String = "{}".into();
+        let data = self
+            .client
+            .get_signed("/conditional_orders".into(), payload)?;
+        let trigger_orders: model::ResultData<Vec<model::TriggerOrderInfo>> =
+            serde_json::from_str(data.as_str())?;
+        Ok(trigger_orders.result)
+    }
+
     pub fn get_open_trigger_orders<S>(
         &self,
         symbol: S,
@@ -65,20 +100,21 @@ impl OrdersClient {
         if let Some(t) = option_type {
             payload = json!({ "type": t }).to_string();
         }
-        let endpoint = format!("/conditional_orders?market={}", symbol.into());
+        let endpoint = with_query("/conditional_orders".into(), &[("market", symbol.into())]);
         let data = self
             .client
-            .get_signed(endpoint.into(), payload.to_string(), None)?;
+            .get_signed(endpoint.into(), payload.to_string())?;
         let trigger_orders: model::ResultData<Vec<model::TriggerOrderInfo>> =
             serde_json::from_str(data.as_str())?;
         Ok(trigger_orders.result)
     }
 
+    // `price` is None for market orders
     pub fn place_order<S>(
         &self,
         symbol: S,
         side: String,
-        price: Decimal,
+        price: Option<Decimal>,
         option_type: String,
         size: Decimal,
         reduce_only: bool,
@@ -104,7 +140,7 @@ impl OrdersClient {
         });
         let data = self
             .client
-            .post_signed("/orders".into(), payload.to_string(), None)?;
+            .post_signed("/orders".into(), payload.to_string())?;
         let order: model::ResultData<model::OrderInfo> = serde_json::from_str(data.as_str())?;
         Ok(order.result)
     }
@@ -130,7 +166,7 @@ impl OrdersClient {
         let endpoint = format!("/orders/{}/modify", order_id);
         let data = self
             .client
-            .post_signed(endpoint.into(), payload.to_string(), None)?;
+            .post_signed(endpoint.into(), payload.to_string())?;
         let order: model::ResultData<model::OrderInfo> = serde_json::from_str(data.as_str())?;
         Ok(order.result)
     }
@@ -148,7 +184,7 @@ impl OrdersClient {
         let endpoint = format!("/orders/by_client_id/{}/modify", client_id);
         let data = self
             .client
-            .post_signed(endpoint.into(), payload.to_string(), None)?;
+            .post_signed(endpoint.into(), payload.to_string())?;
         let order: model::ResultData<model::OrderInfo> = serde_json::from_str(data.as_str())?;
         Ok(order.result)
     }
@@ -156,7 +192,7 @@ impl OrdersClient {
     pub fn get_order_status(&self, order_id: i64) -> Result<model::OrderInfo> {
         let payload: String = format!("{}", "{}");
         let endpoint = format!("/orders/{}", order_id);
-        let data = self.client.get_signed(endpoint.into(), payload, None)?;
+        let data = self.client.get_signed(endpoint.into(), payload)?;
         let order: model::ResultData<model::OrderInfo> = serde_json::from_str(data.as_str())?;
         Ok(order.result)
     }
@@ -164,7 +200,7 @@ impl OrdersClient {
     pub fn get_order_status_by_client_id(&self, client_id: String) -> Result<model::OrderInfo> {
         let payload: String = format!("{}", "{}");
         let endpoint = format!("/orders/by_client_id/{}", client_id);
-        let data = self.client.get_signed(endpoint.into(), payload, None)?;
+        let data = self.client.get_signed(endpoint.into(), payload)?;
         let order: model::ResultData<model::OrderInfo> = serde_json::from_str(data.as_str())?;
         Ok(order.result)
     }
@@ -172,7 +208,7 @@ impl OrdersClient {
     pub fn cancel_order(&self, order_id: i64) -> Result<bool> {
         let payload: String = format!("{}", "{}");
         let endpoint = format!("/orders/{}", order_id);
-        let data = self.client.delete_signed(endpoint.into(), payload, None)?;
+        let data = self.client.delete_signed(endpoint.into(), payload)?;
         let result: model::ResultData<String> = serde_json::from_str(data.as_str())?;
         Ok(result.success)
     }
@@ -180,39 +216,94 @@ impl OrdersClient {
     pub fn cancel_order_by_client_id(&self, client_id: String) -> Result<bool> {
         let payload: String = format!("{}", "{}");
         let endpoint = format!("/orders/by_client_id/{}", client_id);
-        let data = self.client.delete_signed(endpoint.into(), payload, None)?;
+        let data = self.client.delete_signed(endpoint.into(), payload)?;
         println!("{:?}", data);
         let result: model::ResultData<String> = serde_json::from_str(data.as_str())?;
         Ok(result.success)
     }
 
-    pub fn cancel_all_orders<S>(
+    // `market` None cancels across every market of the account
+    pub fn cancel_all_orders(
         &self,
-        symbol: S,
+        market: Option<String>,
         side: Option<String>,
         conditional_orders_only: bool,
         limit_orders_only: bool,
-    ) -> Result<bool>
-    where
-        S: Into<String> + Copy,
-    {
+    ) -> Result<bool> {
         let mut payload = json!({
-            "market": &symbol.into(),
             "conditionalOrdersOnly": conditional_orders_only,
             "limitOrdersOnly": limit_orders_only,
         });
+        if let Some(m) = market {
+            payload["market"] = json!(m);
+        }
         if let Some(s) = side {
-            payload = json!({
-                "market": &symbol.into(),
-                "side": s,
-                "conditionalOrdersOnly": conditional_orders_only,
-                "limitOrdersOnly": limit_orders_only,
-            });
+            payload["side"] = json!(s);
         }
         let data = self
             .client
-            .delete_signed("/orders".into(), payload.to_string(), None)?;
+            .delete_signed("/orders".into(), payload.to_string())?;
         let result: model::ResultData<String> = serde_json::from_str(data.as_str())?;
         Ok(result.success)
     }
+
+    pub fn place_trigger_order(
+        &self,
+        order: &model::NewTriggerOrder,
+    ) -> Result<model::TriggerOrderInfo> {
+        let payload = serde_json::to_string(order)?;
+        let data = self
+            .client
+            .post_signed("/conditional_orders".into(), payload)?;
+        let order: model::ResultData<model::TriggerOrderInfo> =
+            serde_json::from_str(data.as_str())?;
+        Ok(order.result)
+    }
+
+    // FTX replaces the order on modify, so the returned order has a new id
+    pub fn modify_trigger_order(
+        &self,
+        order_id: i64,
+        size: Decimal,
+        trigger_price: Option<Decimal>,
+        order_price: Option<Decimal>,
+        trail_value: Option<Decimal>,
+    ) -> Result<model::TriggerOrderInfo> {
+        let mut payload = json!({ "size": size });
+        if let Some(p) = trigger_price {
+            payload["triggerPrice"] = json!(p);
+        }
+        if let Some(p) = order_price {
+            payload["orderPrice"] = json!(p);
+        }
+        if let Some(t) = trail_value {
+            payload["trailValue"] = json!(t);
+        }
+        let endpoint = format!("/conditional_orders/{}/modify", order_id);
+        let data = self.client.post_signed(endpoint, payload.to_string())?;
+        let order: model::ResultData<model::TriggerOrderInfo> =
+            serde_json::from_str(data.as_str())?;
+        Ok(order.result)
+    }
+
+    pub fn cancel_trigger_order(&self, order_id: i64) -> Result<bool> {
+        let payload: String = "{}".into();
+        let endpoint = format!("/conditional_orders/{}", order_id);
+        let data = self.client.delete_signed(endpoint, payload)?;
+        let result: model::ResultData<String> = serde_json::from_str(data.as_str())?;
+        Ok(result.success)
+    }
+
+    // orders a trigger order has sent so far
+    pub fn get_trigger_order_triggers(
+        &self,
+        order_id: i64,
+    ) -> Result<Vec<model::TriggerOrderTrigger>> {
+        let payload: String = "{}".into();
+        let endpoint = format!("/conditional_orders/{}/triggers", order_id);
+        let data = self.client.get_signed(endpoint, payload)?;
+        let triggers: model::ResultData<Vec<model::TriggerOrderTrigger>> =
+            serde_json::from_str(data.as_str())?;
+        Ok(triggers.result)
+    }
 }
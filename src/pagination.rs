@@ -0,0 +1,85 @@
+use crate::errors::*;
+use crate::timestamps::TimeRange;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashSet;
+use std::hash::Hash;
+
+// FTX history endpoints return a capped page of the newest rows in the window,
+// so walk backwards by moving `end_time` to the oldest row seen, dropping rows
+// repeated on the page boundary. Results stay newest first.
+pub(crate) fn paginate<T, K, F, TF, KF>(
+    range: TimeRange,
+    mut fetch: F,
+    time_of: TF,
+    key_of: KF,
+) -> Result<Vec<T>>
+where
+    K: Eq + Hash,
+    F: FnMut(TimeRange) -> Result<Vec<T>>,
+    TF: Fn(&T) -> DateTime<Utc>,
+    KF: Fn(&T) -> K,
+{
+    let mut seen: HashSet<K> = HashSet::new();
+    let mut rows: Vec<T> = Vec::new();
+    let mut end = range.end;
+    loop {
+        let page = fetch(TimeRange::new(range.start, end))?;
+        let oldest = match page.iter().map(&time_of).min() {
+            Some(t) => t,
+            None => break,
+        };
+
+        let mut added = 0;
+        for row in page {
+            if seen.insert(key_of(&row)) {
+                rows.push(row);
+                added += 1;
+            }
+        }
+
+        // a page made only of rows sharing the oldest timestamp cannot move the
+        // window, so step past that second instead
+        let next = if end == Some(oldest) {
+            oldest - Duration::seconds(1)
+        } else if added == 0 {
+            break;
+        } else {
+            oldest
+        };
+        if range.start.is_some_and(|start| next < start) {
+            break;
+        }
+        end = Some(next);
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timestamps::from_seconds;
+
+    #[test]
+    fn test_paginate() {
+        // two rows per second from 0..100, newest first, pages of 7
+        let all: Vec<(i64, i64)> = (0..200).rev().map(|i| (i / 2, i)).collect();
+        let rows = paginate(
+            TimeRange::new(Some(from_seconds(10)), None),
+            |r| {
+                Ok(all
+                    .iter()
+                    .filter(|(t, _)| r.end.is_none_or(|e| *t <= e.timestamp()))
+                    .filter(|(t, _)| r.start.is_none_or(|s| *t >= s.timestamp()))
+                    .take(7)
+                    .cloned()
+                    .collect())
+            },
+            |row| from_seconds(row.0),
+            |row| row.1,
+        )
+        .unwrap();
+        assert_eq!(rows.len(), 180);
+        assert_eq!(rows.first(), Some(&(99, 199)));
+        assert_eq!(rows.last(), Some(&(10, 20)));
+    }
+}
@@ -0,0 +1,351 @@
+use crate::errors::*;
+use crate::events::TickerInfo;
+use crate::execution::round_price;
+use crate::model::{self, OrderStatus, Side};
+use crate::orders::OrdersClient;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PegParams {
+    pub market: String,
+    pub side: Side,
+    pub size: Decimal,
+    // distance behind the touch: a buy rests at best bid - offset
+    pub offset: Decimal,
+    // how far the order may follow the market from where it was first placed
+    pub max_chase: Option<Decimal>,
+    pub price_increment: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PegState {
+    Idle,
+    Working,
+    Filled,
+    Cancelled,
+}
+
+// A post-only order kept at the best bid (buys) or best ask (sells) by
+// re-pricing it with `modify_order` as the ticker or book moves.
+pub struct PeggedOrder {
+    orders: OrdersClient,
+    pub params: PegParams,
+    pub state: PegState,
+    // the live order; modify replaces it, so the id changes over time
+    pub order: Option<model::Order>,
+    pub filled: Decimal,
+    // price of the first placement, what max_chase is measured from
+    anchor: Option<Decimal>,
+    // filled size of earlier orders that have closed
+    filled_before: Decimal,
+    // orders replaced by modify, which can still fill until their close arrives
+    replaced: HashMap<i64, Decimal>,
+    touch: Option<(Decimal, Decimal)>,
+    cancelling: bool,
+}
+
+impl PeggedOrder {
+    pub fn new(orders: OrdersClient, params: PegParams) -> Self {
+        PeggedOrder {
+            orders,
+            params,
+            state: PegState::Idle,
+            order: None,
+            filled: Decimal::ZERO,
+            anchor: None,
+            filled_before: Decimal::ZERO,
+            replaced: HashMap::new(),
+            touch: None,
+            cancelling: false,
+        }
+    }
+
+    pub fn remaining(&self) -> Decimal {
+        (self.params.size - self.filled).max(Decimal::ZERO)
+    }
+
+    pub fn on_ticker(&mut self, ticker: &TickerInfo) -> Result<()> {
+        self.on_touch(ticker.bid, ticker.ask)
+    }
+
+    pub fn on_depth(&mut self, depth: &model::Depth) -> Result<()> {
+        match (depth.best_bid(), depth.best_ask()) {
+            (Some(bid), Some(ask)) => self.on_touch(bid.price, ask.price),
+            _ => Ok(()),
+        }
+    }
+
+    // place the order on the first quote, then follow the touch
+    pub fn on_touch(&mut self, bid: Decimal, ask: Decimal) -> Result<()> {
+        self.touch = Some((bid, ask));
+        match self.state {
+            PegState::Idle => self.place(),
+            PegState::Working => self.reprice(),
+            _ => Ok(()),
+        }
+    }
+
+    // keep fills in step; a post-only order the exchange pulled is put back
+    pub fn on_order(&mut self, order: &model::Order) -> Result<()> {
+        if !self.record(order) {
+            return Ok(());
+        }
+        if self.order.is_some() {
+            if self.cancelling {
+                return Ok(());
+            }
+            // late fills on a replaced order may have done the job already
+            if self.remaining().is_zero() {
+                return self.cancel();
+            }
+            // or part of it, leaving the live order bigger than what is left
+            if let Some(size) = self.needs_shrink() {
+                return self.shrink(size);
+            }
+            return Ok(());
+        }
+        if self.remaining().is_zero() {
+            self.state = PegState::Filled;
+        } else if self.cancelling {
+            self.state = PegState::Cancelled;
+        } else {
+            return self.place();
+        }
+        Ok(())
+    }
+
+    pub fn cancel(&mut self) -> Result<()> {
+        self.cancelling = true;
+        match &self.order {
+            Some(order) => {
+                self.orders.cancel_order(order.id)?;
+            }
+            None => self.state = PegState::Cancelled,
+        }
+        Ok(())
+    }
+
+    // Book an update for the live order or one it replaced; false if it is
+    // neither. A closed live order is cleared.
+    fn record(&mut self, order: &model::Order) -> bool {
+        if let Some(filled) = self.replaced.get_mut(&order.id) {
+            *filled = (*filled).max(order.filled_size);
+            if order.status == OrderStatus::Closed {
+                self.filled_before += self.replaced.remove(&order.id).unwrap_or_default();
+            }
+        } else if self.order.as_ref().is_some_and(|o| o.id == order.id) {
+            if order.status == OrderStatus::Closed {
+                self.filled_before += order.filled_size;
+                self.order = None;
+            } else {
+                self.order = Some(order.clone());
+            }
+        } else {
+            return false;
+        }
+        self.refresh_filled();
+        true
+    }
+
+    // `modified` took over from the live order, which keeps counting until it closes
+    fn replace(&mut self, modified: model::Order) {
+        if let Some(old) = self.order.take() {
+            self.replaced.insert(old.id, old.filled_size);
+        }
+        self.order = Some(modified);
+        self.refresh_filled();
+    }
+
+    fn refresh_filled(&mut self) {
+        let live = self
+            .order
+            .as_ref()
+            .map(|o| o.filled_size)
+            .unwrap_or_default();
+        self.filled = self.filled_before + self.replaced.values().sum::<Decimal>() + live;
+    }
+
+    // what the live order should be cut to, if it would fill past `size`
+    fn needs_shrink(&self) -> Option<Decimal> {
+        let order = self.order.as_ref()?;
+        let remaining = self.remaining();
+        (order.size - order.filled_size > remaining).then_some(remaining)
+    }
+
+    // resize the live order where it stands
+    fn shrink(&mut self, size: Decimal) -> Result<()> {
+        let (id, price) = match &self.order {
+            Some(model::Order {
+                id,
+                price: Some(price),
+                ..
+            }) => (*id, *price),
+            _ => return Ok(()),
+        };
+        let modified = self.orders.modify_order(id, price, size, None)?;
+        self.replace(modified.into());
+        Ok(())
+    }
+
+    fn target(&self) -> Option<Decimal> {
+        let (bid, ask) = self.touch?;
+        Some(target_price(&self.params, bid, ask, self.anchor))
+    }
+
+    fn place(&mut self) -> Result<()> {
+        let price = match self.target() {
+            Some(p) => p,
+            None => return Ok(()),
+        };
+        let order = self.orders.place_order(
+            self.params.market.as_str(),
+            self.params.side.as_str().to_string(),
+            Some(price),
+            "limit".to_string(),
+            self.remaining(),
+            false,
+            false,
+            true,
+            None,
+            false,
+        )?;
+        self.anchor.get_or_insert(price);
+        self.order = Some(order.into());
+        self.state = PegState::Working;
+        Ok(())
+    }
+
+    fn reprice(&mut self) -> Result<()> {
+        let (id, price) = match (&self.order, self.target()) {
+            (Some(o), Some(p)) if o.price != Some(p) && !self.cancelling => (o.id, p),
+            _ => return Ok(()),
+        };
+        // sized from every fill seen so far, not the live order's own remainder
+        let size = self.remaining();
+        if size.is_zero() {
+            return Ok(());
+        }
+        let modified = self.orders.modify_order(id, price, size, None)?;
+        self.replace(modified.into());
+        Ok(())
+    }
+}
+
+// The touch moved `offset` further from the spread, clamped to `max_chase` from
+// `anchor` and rounded away from the spread.
+pub fn target_price(
+    params: &PegParams,
+    bid: Decimal,
+    ask: Decimal,
+    anchor: Option<Decimal>,
+) -> Decimal {
+    let mut price = match params.side {
+        Side::Buy => bid - params.offset,
+        Side::Sell => ask + params.offset,
+    };
+    if let (Some(anchor), Some(max_chase)) = (anchor, params.max_chase) {
+        price = match params.side {
+            Side::Buy => price.min(anchor + max_chase),
+            Side::Sell => price.max(anchor - max_chase),
+        };
+    }
+    match params.price_increment {
+        Some(increment) => round_price(price, increment, params.side),
+        None => price,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dec(s: &str) -> Decimal {
+        Decimal::from_str_exact(s).unwrap()
+    }
+
+    fn update(id: i64, size: &str, filled: &str, status: &str) -> model::Order {
+        serde_json::from_value(json!({
+            "id": id, "clientId": null, "market": "BTC-PERP", "future": "BTC-PERP",
+            "type": "limit", "side": "buy", "price": 100, "size": size, "status": status,
+            "filledSize": filled, "remainingSize": "0", "avgFillPrice": null,
+            "reduceOnly": false, "ioc": false, "postOnly": true, "liquidation": false,
+            "createdAt": null
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_replaced_order_fills() {
+        let params = PegParams {
+            market: "BTC-PERP".into(),
+            side: Side::Buy,
+            size: dec("10"),
+            offset: Decimal::ZERO,
+            max_chase: None,
+            price_increment: None,
+        };
+        let mut peg = PeggedOrder::new(OrdersClient::new(None, None), params);
+        peg.order = Some(update(1, "10", "0", "open"));
+        peg.on_order(&update(1, "10", "2", "open")).unwrap();
+        assert_eq!(peg.remaining(), dec("8"));
+
+        // order 1 fills to 5 before the modify, but the last update said 2
+        peg.replace(update(2, "8", "0", "new"));
+        assert_eq!(peg.filled, dec("2"));
+
+        // updates for the replaced order are still counted
+        assert!(peg.record(&update(1, "10", "5", "closed")));
+        assert_eq!(peg.needs_shrink(), Some(dec("5")));
+        assert!(peg.record(&update(2, "8", "1", "open")));
+        assert_eq!(peg.filled, dec("6"));
+        // the next replacement is sized from all fills, not order 2's remainder
+        assert_eq!(peg.remaining(), dec("4"));
+        assert!(peg.replaced.is_empty());
+        // order 2 still shows 7, which would overfill; on_order cuts it to 4
+        assert_eq!(peg.needs_shrink(), Some(dec("4")));
+        peg.replace(update(3, "4", "0", "new"));
+        assert_eq!(peg.needs_shrink(), None);
+        assert_eq!(peg.filled, dec("6"));
+
+        // unrelated orders are ignored
+        peg.on_order(&update(9, "1", "1", "closed")).unwrap();
+        assert_eq!(peg.filled, dec("6"));
+    }
+
+    #[test]
+    fn test_target_price() {
+        let mut params = PegParams {
+            market: "BTC-PERP".into(),
+            side: Side::Buy,
+            size: dec("1"),
+            offset: dec("0.5"),
+            max_chase: Some(dec("10")),
+            price_increment: Some(dec("1")),
+        };
+        assert_eq!(
+            target_price(&params, dec("100"), dec("101"), None),
+            dec("99")
+        );
+        // chasing up stops 10 above where the order started
+        assert_eq!(
+            target_price(&params, dec("120"), dec("121"), Some(dec("99"))),
+            dec("109")
+        );
+        // but it follows the market down freely
+        assert_eq!(
+            target_price(&params, dec("80"), dec("81"), Some(dec("99"))),
+            dec("79")
+        );
+
+        params.side = Side::Sell;
+        assert_eq!(
+            target_price(&params, dec("100"), dec("101"), None),
+            dec("102")
+        );
+        assert_eq!(
+            target_price(&params, dec("80"), dec("81"), Some(dec("102"))),
+            dec("92")
+        );
+    }
+}
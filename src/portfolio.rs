@@ -0,0 +1,216 @@
+use crate::api::Ftx;
+use crate::errors::*;
+use crate::model;
+use crate::subaccounts::MAIN_ACCOUNT;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::thread;
+
+// Everything held by one account, fetched with that account's credentials.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountSnapshot {
+    pub name: String,
+    pub account: model::AccountInfo,
+    pub balances: Vec<model::BalanceInfo>,
+    pub positions: Vec<model::Position>,
+    pub open_orders: Vec<model::OrderInfo>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CoinTotal {
+    pub total: Decimal,
+    pub free: Decimal,
+    pub spot_borrow: Decimal,
+    pub usd_value: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketTotal {
+    pub net_size: Decimal,
+    pub usd_notional: Decimal,
+    pub unrealized_pnl: Decimal,
+    pub open_order_size: Decimal,
+    pub open_order_usd: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountTotal {
+    pub balances_usd: Decimal,
+    pub collateral: Decimal,
+    pub free_collateral: Decimal,
+    pub position_notional: Decimal,
+    pub unrealized_pnl: Decimal,
+    pub margin_fraction: Option<Decimal>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Portfolio {
+    pub accounts: Vec<AccountSnapshot>,
+}
+
+impl Ftx {
+    // Snapshot the main account and every subaccount, one thread per account.
+    pub fn get_portfolio(&self) -> Result<Portfolio> {
+        let mut names = vec![MAIN_ACCOUNT.to_string()];
+        names.extend(
+            self.subaccounts
+                .get_subaccounts()?
+                .into_iter()
+                .map(|s| s.nickname),
+        );
+
+        let results: Vec<Result<AccountSnapshot>> = thread::scope(|scope| {
+            let handles: Vec<_> = names
+                .iter()
+                .map(|name| scope.spawn(move || self.get_account_snapshot(name)))
+                .collect();
+            handles
+                .into_iter()
+                .map(|h| match h.join() {
+                    Ok(r) => r,
+                    Err(_) => bail!("Portfolio worker panicked"),
+                })
+                .collect()
+        });
+
+        let accounts = results.into_iter().collect::<Result<Vec<_>>>()?;
+        Ok(Portfolio { accounts })
+    }
+
+    // `name` is a subaccount nickname or MAIN_ACCOUNT
+    pub fn get_account_snapshot(&self, name: &str) -> Result<AccountSnapshot> {
+        let api = self.for_account(name);
+        Ok(AccountSnapshot {
+            name: name.to_string(),
+            account: api.account.get_account()?,
+            balances: api.wallet.get_balances()?,
+            positions: api.account.get_positions()?,
+            open_orders: api.orders.get_all_open_orders()?,
+        })
+    }
+}
+
+// cost + unrealized pnl is the position's current signed value
+fn position_value(position: &model::Position) -> Decimal {
+    position.cost + position.unrealized_pnl
+}
+
+impl Portfolio {
+    pub fn total_usd_value(&self) -> Decimal {
+        self.accounts
+            .iter()
+            .flat_map(|a| a.balances.iter())
+            .map(|b| b.usd_value)
+            .sum()
+    }
+
+    pub fn coin_totals(&self) -> BTreeMap<String, CoinTotal> {
+        let mut totals: BTreeMap<String, CoinTotal> = BTreeMap::new();
+        for balance in self.accounts.iter().flat_map(|a| a.balances.iter()) {
+            let t = totals.entry(balance.coin.clone()).or_default();
+            t.total += balance.total;
+            t.free += balance.free;
+            t.spot_borrow += balance.spot_borrow;
+            t.usd_value += balance.usd_value;
+        }
+        totals
+    }
+
+    pub fn market_totals(&self) -> BTreeMap<String, MarketTotal> {
+        let mut totals: BTreeMap<String, MarketTotal> = BTreeMap::new();
+        for account in &self.accounts {
+            for position in account.positions.iter().filter(|p| !p.size.is_zero()) {
+                let t = totals.entry(position.future.clone()).or_default();
+                t.net_size += position.net_size;
+                t.usd_notional += position_value(position).abs();
+                t.unrealized_pnl += position.unrealized_pnl;
+            }
+            for order in &account.open_orders {
+                let t = totals.entry(order.market.clone()).or_default();
+                t.open_order_size += order.remaining_size;
+                t.open_order_usd += order.remaining_size * order.price.unwrap_or_default();
+            }
+        }
+        totals
+    }
+
+    pub fn account_totals(&self) -> BTreeMap<String, AccountTotal> {
+        self.accounts
+            .iter()
+            .map(|a| {
+                let notional = a.positions.iter().map(|p| position_value(p).abs());
+                let total = AccountTotal {
+                    balances_usd: a.balances.iter().map(|b| b.usd_value).sum(),
+                    collateral: a.account.collateral,
+                    free_collateral: a.account.free_collateral,
+                    position_notional: notional.sum(),
+                    unrealized_pnl: a.positions.iter().map(|p| p.unrealized_pnl).sum(),
+                    margin_fraction: a.account.margin_fraction,
+                };
+                (a.name.clone(), total)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(name: &str, usd: i64, net_size: i64, cost: i64) -> AccountSnapshot {
+        let position = json!({
+            "cost": cost, "entryPrice": 100, "future": "BTC-PERP",
+            "initialMarginRequirement": 0.1, "longOrderSize": 0,
+            "maintenanceMarginRequirement": 0.03, "netSize": net_size, "openSize": 0,
+            "realizedPnl": 0, "shortOrderSize": 0, "side": "buy",
+            "size": net_size.abs(), "unrealizedPnl": 5
+        });
+        let account = json!({
+            "accountIdentifier": 1, "username": name, "collateral": usd,
+            "freeCollateral": usd, "totalAccountValue": usd, "totalPositionSize": 0,
+            "initialMarginRequirement": 0.1, "maintenanceMarginRequirement": 0.03,
+            "marginFraction": null, "openMarginFraction": null, "liquidating": false,
+            "backstopProvider": false, "positions": [], "takerFee": 0.0007,
+            "makerFee": 0.0002, "leverage": 10, "positionLimit": null,
+            "positionLimitUsed": null, "useFttCollateral": true,
+            "chargeInterestOnNegativeUsd": false, "spotMarginEnabled": false,
+            "spotLendingEnabled": false
+        });
+        let balance = json!({
+            "coin": "USD", "total": usd, "free": usd, "availableWithoutBorrow": usd,
+            "usdValue": usd, "spotBorrow": 0
+        });
+        AccountSnapshot {
+            name: name.to_string(),
+            account: serde_json::from_value(account).unwrap(),
+            balances: vec![serde_json::from_value(balance).unwrap()],
+            positions: vec![serde_json::from_value(position).unwrap()],
+            open_orders: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_totals() {
+        let portfolio = Portfolio {
+            accounts: vec![
+                snapshot("main", 1000, 1, 100),
+                snapshot("sub", 500, -2, -200),
+            ],
+        };
+        assert_eq!(portfolio.total_usd_value(), Decimal::from(1500));
+        assert_eq!(portfolio.coin_totals()["USD"].total, Decimal::from(1500));
+        let btc = &portfolio.market_totals()["BTC-PERP"];
+        assert_eq!(btc.net_size, Decimal::from(-1));
+        assert_eq!(btc.usd_notional, Decimal::from(300));
+        assert_eq!(
+            portfolio.account_totals()["sub"].position_notional,
+            Decimal::from(195)
+        );
+    }
+}
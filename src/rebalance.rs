@@ -0,0 +1,369 @@
+use crate::api::Ftx;
+use crate::errors::*;
+use crate::model;
+use crate::subaccounts::MAIN_ACCOUNT;
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fmt;
+
+// transfer sizes are rounded down to this many decimals
+const SIZE_DECIMALS: u32 = 8;
+
+// Where a subaccount's collateral should sit. Nothing moves while the account is
+// inside [min, max]; once outside it is brought back to `target`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum Band {
+    // collateral in USD
+    Collateral {
+        min: Decimal,
+        target: Decimal,
+        max: Decimal,
+    },
+    // collateral / position notional
+    MarginFraction {
+        min: Decimal,
+        target: Decimal,
+        max: Decimal,
+    },
+}
+
+impl Band {
+    // USD to add (positive) or release (negative) to bring the account back to target
+    pub fn adjustment(&self, collateral: Decimal, position_notional: Decimal) -> Decimal {
+        let (min, target, max) = match *self {
+            Band::Collateral { min, target, max } => (min, target, max),
+            Band::MarginFraction { min, target, max } => {
+                // margin fraction is undefined without positions
+                if position_notional.is_zero() {
+                    return Decimal::ZERO;
+                }
+                (
+                    min * position_notional,
+                    target * position_notional,
+                    max * position_notional,
+                )
+            }
+        };
+        if collateral < min || collateral > max {
+            target - collateral
+        } else {
+            Decimal::ZERO
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountState {
+    pub name: String,
+    pub collateral: Decimal,
+    pub free_collateral: Decimal,
+    pub position_notional: Decimal,
+    // free balance of the transfer coin, in USD
+    pub available_usd: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedTransfer {
+    pub coin: String,
+    pub size: Decimal,
+    pub usd_value: Decimal,
+    pub source: String,
+    pub destination: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RebalancePlan {
+    pub coin: String,
+    pub price: Decimal,
+    pub accounts: Vec<AccountState>,
+    pub transfers: Vec<PlannedTransfer>,
+    // USD that neither the main account nor other subaccounts could cover
+    pub shortfall: Decimal,
+}
+
+impl fmt::Display for RebalancePlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.transfers.is_empty() {
+            writeln!(f, "nothing to transfer")?;
+        }
+        for t in &self.transfers {
+            writeln!(
+                f,
+                "{} {} (${}) {} -> {}",
+                t.size, t.coin, t.usd_value, t.source, t.destination
+            )?;
+        }
+        if self.shortfall > Decimal::ZERO {
+            writeln!(f, "shortfall: ${}", self.shortfall)?;
+        }
+        Ok(())
+    }
+}
+
+// Moves collateral between the main account and subaccounts in a single coin.
+// The main account is the pool: it funds deficits the other subaccounts can't
+// cover and receives any surplus left over.
+#[derive(Clone)]
+pub struct Rebalancer {
+    api: Ftx,
+    pub coin: String,
+    pub bands: BTreeMap<String, Band>,
+    // USD the main account always keeps
+    pub main_reserve: Decimal,
+}
+
+impl Rebalancer {
+    pub fn new<S>(api: Ftx, coin: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Rebalancer {
+            api,
+            coin: coin.into(),
+            bands: BTreeMap::new(),
+            main_reserve: Decimal::ZERO,
+        }
+    }
+
+    pub fn set_band<S>(&mut self, subaccount: S, band: Band)
+    where
+        S: Into<String>,
+    {
+        self.bands.insert(subaccount.into(), band);
+    }
+
+    // dry run: fetch every banded account and work out the transfers
+    pub fn plan(&self) -> Result<RebalancePlan> {
+        let main_balances = self.api.for_main_account().wallet.get_balances()?;
+        let mut price = coin_price(&main_balances, &self.coin);
+        let main_available = available_usd(&main_balances, &self.coin) - self.main_reserve;
+
+        let mut accounts = Vec::new();
+        for name in self.bands.keys() {
+            let api = self.api.for_account(name);
+            let account = api.account.get_account()?;
+            let balances = api.wallet.get_balances()?;
+            price = price.or_else(|| coin_price(&balances, &self.coin));
+            accounts.push(AccountState {
+                name: name.clone(),
+                collateral: account.collateral,
+                free_collateral: account.free_collateral,
+                position_notional: account.total_position_size,
+                available_usd: available_usd(&balances, &self.coin),
+            });
+        }
+
+        let price = match price {
+            Some(p) => p,
+            None => bail!(format!("No USD price for {}", self.coin)),
+        };
+        let (moves, shortfall) = plan_moves(&accounts, &self.bands, main_available);
+        let transfers = moves
+            .into_iter()
+            .map(|(source, destination, usd)| {
+                let size =
+                    (usd / price).round_dp_with_strategy(SIZE_DECIMALS, RoundingStrategy::ToZero);
+                PlannedTransfer {
+                    coin: self.coin.clone(),
+                    size,
+                    usd_value: size * price,
+                    source,
+                    destination,
+                }
+            })
+            .filter(|t| t.size > Decimal::ZERO)
+            .collect();
+
+        Ok(RebalancePlan {
+            coin: self.coin.clone(),
+            price,
+            accounts,
+            transfers,
+            shortfall,
+        })
+    }
+
+    // run the transfers of `plan` in order, stopping at the first failure
+    pub fn execute(&self, plan: &RebalancePlan) -> Result<Vec<model::TransferInfo>> {
+        let mut done = Vec::new();
+        for t in &plan.transfers {
+            done.push(self.api.subaccounts.transfer_subaccounts(
+                t.coin.clone(),
+                t.size,
+                t.source.clone(),
+                t.destination.clone(),
+            )?);
+        }
+        Ok(done)
+    }
+
+    pub fn rebalance(&self) -> Result<Vec<model::TransferInfo>> {
+        let plan = self.plan()?;
+        self.execute(&plan)
+    }
+}
+
+fn coin_price(balances: &[model::BalanceInfo], coin: &str) -> Option<Decimal> {
+    if coin == "USD" {
+        return Some(Decimal::ONE);
+    }
+    balances
+        .iter()
+        .find(|b| b.coin == coin && !b.total.is_zero())
+        .map(|b| b.usd_value / b.total)
+}
+
+fn available_usd(balances: &[model::BalanceInfo], coin: &str) -> Decimal {
+    balances
+        .iter()
+        .find(|b| b.coin == coin)
+        .and_then(|b| {
+            let price = coin_price(balances, coin)?;
+            Some(b.free.max(Decimal::ZERO) * price)
+        })
+        .unwrap_or_default()
+}
+
+// Pair the largest surpluses with the largest deficits directly, then fall back
+// to the main account. Returns (source, destination, usd) moves and any shortfall.
+pub fn plan_moves(
+    accounts: &[AccountState],
+    bands: &BTreeMap<String, Band>,
+    main_available: Decimal,
+) -> (Vec<(String, String, Decimal)>, Decimal) {
+    let mut deficits: Vec<(String, Decimal)> = Vec::new();
+    let mut surpluses: Vec<(String, Decimal)> = Vec::new();
+    for account in accounts {
+        let band = match bands.get(&account.name) {
+            Some(b) => b,
+            None => continue,
+        };
+        let adjustment = band.adjustment(account.collateral, account.position_notional);
+        if adjustment > Decimal::ZERO {
+            deficits.push((account.name.clone(), adjustment));
+        } else if adjustment < Decimal::ZERO {
+            let releasable = (-adjustment)
+                .min(account.free_collateral)
+                .min(account.available_usd);
+            if releasable > Decimal::ZERO {
+                surpluses.push((account.name.clone(), releasable));
+            }
+        }
+    }
+    deficits.sort_by_key(|d| Reverse(d.1));
+    surpluses.sort_by_key(|s| Reverse(s.1));
+
+    let mut moves = Vec::new();
+    let mut s = 0;
+    for deficit in deficits.iter_mut() {
+        while deficit.1 > Decimal::ZERO && s < surpluses.len() {
+            let size = deficit.1.min(surpluses[s].1);
+            moves.push((surpluses[s].0.clone(), deficit.0.clone(), size));
+            deficit.1 -= size;
+            surpluses[s].1 -= size;
+            if surpluses[s].1.is_zero() {
+                s += 1;
+            }
+        }
+    }
+
+    let mut main_available = main_available.max(Decimal::ZERO);
+    let mut shortfall = Decimal::ZERO;
+    for (name, needed) in deficits.into_iter().filter(|d| d.1 > Decimal::ZERO) {
+        // a banded main account can't fund itself
+        let size = if name == MAIN_ACCOUNT {
+            Decimal::ZERO
+        } else {
+            needed.min(main_available)
+        };
+        if size > Decimal::ZERO {
+            moves.push((MAIN_ACCOUNT.to_string(), name, size));
+            main_available -= size;
+        }
+        shortfall += needed - size;
+    }
+    for (name, left) in surpluses
+        .into_iter()
+        .filter(|s| s.1 > Decimal::ZERO && s.0 != MAIN_ACCOUNT)
+    {
+        moves.push((name, MAIN_ACCOUNT.to_string(), left));
+    }
+    (moves, shortfall)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(v: i64) -> Decimal {
+        Decimal::from(v)
+    }
+
+    fn state(name: &str, collateral: i64, position_notional: i64) -> AccountState {
+        AccountState {
+            name: name.into(),
+            collateral: d(collateral),
+            free_collateral: d(collateral),
+            position_notional: d(position_notional),
+            available_usd: d(collateral),
+        }
+    }
+
+    #[test]
+    fn test_plan_moves() {
+        let mut bands = BTreeMap::new();
+        let collateral = Band::Collateral {
+            min: d(900),
+            target: d(1000),
+            max: d(1100),
+        };
+        bands.insert("a".to_string(), collateral);
+        bands.insert("b".to_string(), collateral);
+        bands.insert("c".to_string(), collateral);
+        // 10% margin fraction wanted on 5000 notional
+        bands.insert(
+            "mf".to_string(),
+            Band::MarginFraction {
+                min: Decimal::new(5, 2),
+                target: Decimal::new(10, 2),
+                max: Decimal::new(20, 2),
+            },
+        );
+        let accounts = vec![
+            state("a", 1500, 0),
+            state("b", 700, 0),
+            state("c", 1050, 0),
+            state("mf", 200, 5000),
+        ];
+
+        let (moves, shortfall) = plan_moves(&accounts, &bands, d(100));
+        assert_eq!(
+            moves,
+            vec![
+                ("a".to_string(), "b".to_string(), d(300)),
+                ("a".to_string(), "mf".to_string(), d(200)),
+                ("main".to_string(), "mf".to_string(), d(100)),
+            ]
+        );
+        assert_eq!(shortfall, d(0));
+
+        let (_, shortfall) = plan_moves(&accounts, &bands, d(0));
+        assert_eq!(shortfall, d(100));
+
+        // a band on the main account only trades with subaccounts
+        bands.insert("main".to_string(), collateral);
+        let accounts = vec![state("main", 1500, 0), state("b", 1200, 0)];
+        let (moves, _) = plan_moves(&accounts, &bands, d(1000));
+        assert_eq!(moves, vec![("b".to_string(), "main".to_string(), d(200))]);
+        let accounts = vec![state("main", 500, 0)];
+        let (moves, shortfall) = plan_moves(&accounts, &bands, d(1000));
+        assert!(moves.is_empty());
+        assert_eq!(shortfall, d(500));
+    }
+}
@@ -0,0 +1,502 @@
+use crate::errors::*;
+use crate::events::TickerInfo;
+use crate::model::{self, OrderStatus, Side};
+use crate::orders::OrdersClient;
+use rust_decimal::Decimal;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+// Pre-trade limits; `None` disables a check.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RiskLimits {
+    // price * size of a single order, in USD
+    pub max_order_notional: Option<Decimal>,
+    // position plus resting orders on the same side, per market, in USD
+    pub max_position_notional: Option<Decimal>,
+    // largest allowed distance from the reference price, e.g. 0.05 for 5%
+    pub price_collar: Option<Decimal>,
+    pub max_open_orders: Option<usize>,
+    pub max_orders_per_minute: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RiskViolation {
+    OrderRate {
+        limit: usize,
+    },
+    OpenOrders {
+        open: usize,
+        limit: usize,
+    },
+    OrderNotional {
+        notional: Decimal,
+        limit: Decimal,
+    },
+    PositionNotional {
+        market: String,
+        projected: Decimal,
+        limit: Decimal,
+    },
+    PriceCollar {
+        market: String,
+        price: Decimal,
+        reference: Decimal,
+        collar: Decimal,
+    },
+    NoReferencePrice {
+        market: String,
+    },
+}
+
+impl fmt::Display for RiskViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RiskViolation::OrderRate { limit } => {
+                write!(f, "more than {} orders in the last minute", limit)
+            }
+            RiskViolation::OpenOrders { open, limit } => {
+                write!(f, "{} open orders, limit is {}", open, limit)
+            }
+            RiskViolation::OrderNotional { notional, limit } => {
+                write!(f, "order notional {} exceeds {}", notional, limit)
+            }
+            RiskViolation::PositionNotional {
+                market,
+                projected,
+                limit,
+            } => write!(
+                f,
+                "{} position would reach {} notional, limit is {}",
+                market, projected, limit
+            ),
+            RiskViolation::PriceCollar {
+                market,
+                price,
+                reference,
+                collar,
+            } => write!(
+                f,
+                "{} price {} is more than {} away from {}",
+                market, price, collar, reference
+            ),
+            RiskViolation::NoReferencePrice { market } => {
+                write!(f, "no reference price for {}", market)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct OpenOrder {
+    market: String,
+    side: Side,
+    remaining: Decimal,
+    reduce_only: bool,
+}
+
+// What the checks know about the account. Kept current from REST snapshots via
+// `sync`, from websocket updates via `on_fill`/`on_order`/`on_ticker`, and from
+// the responses to orders sent through the guard.
+#[derive(Debug, Default)]
+struct RiskState {
+    reference_prices: HashMap<String, Decimal>,
+    positions: HashMap<String, Decimal>,
+    open_orders: HashMap<i64, OpenOrder>,
+    recent: VecDeque<Instant>,
+}
+
+impl RiskState {
+    fn track(&mut self, order: &model::Order) {
+        if order.status == OrderStatus::Closed || order.remaining_size.is_zero() {
+            self.open_orders.remove(&order.id);
+        } else {
+            self.open_orders.insert(
+                order.id,
+                OpenOrder {
+                    market: order.market.clone(),
+                    side: order.side,
+                    remaining: order.remaining_size,
+                    reduce_only: order.reduce_only,
+                },
+            );
+        }
+    }
+
+    fn resting(&self, market: &str, side: Side, skip: Option<i64>) -> Decimal {
+        self.open_orders
+            .iter()
+            .filter(|(id, o)| Some(**id) != skip && o.market == market && o.side == side)
+            .map(|(_, o)| o.remaining)
+            .sum()
+    }
+}
+
+// an order about to be sent; `price` is None for market orders and
+// `replaces` is the order a modify would cancel, so its size is not counted twice
+#[derive(Clone, Copy)]
+struct Candidate<'a> {
+    market: &'a str,
+    side: Side,
+    price: Option<Decimal>,
+    size: Decimal,
+    reduce_only: bool,
+    replaces: Option<i64>,
+}
+
+impl RiskLimits {
+    fn check(
+        &self,
+        state: &RiskState,
+        order: &Candidate,
+        now: Instant,
+    ) -> std::result::Result<(), RiskViolation> {
+        let Candidate {
+            market,
+            side,
+            price,
+            size,
+            reduce_only,
+            replaces,
+        } = *order;
+        if let Some(limit) = self.max_orders_per_minute {
+            let sent = state
+                .recent
+                .iter()
+                .filter(|t| now.duration_since(**t) < RATE_WINDOW)
+                .count();
+            if sent >= limit {
+                return Err(RiskViolation::OrderRate { limit });
+            }
+        }
+
+        if let Some(limit) = self.max_open_orders {
+            let open = state.open_orders.len();
+            if replaces.is_none() && open >= limit {
+                return Err(RiskViolation::OpenOrders { open, limit });
+            }
+        }
+
+        let reference = state.reference_prices.get(market).cloned();
+        let no_reference = || RiskViolation::NoReferencePrice {
+            market: market.to_string(),
+        };
+        // market orders are valued at the reference price
+        let value_price = price.or(reference);
+
+        if let Some(limit) = self.max_order_notional {
+            let notional = value_price.ok_or_else(no_reference)? * size;
+            if notional > limit {
+                return Err(RiskViolation::OrderNotional { notional, limit });
+            }
+        }
+
+        if let (Some(collar), Some(price)) = (self.price_collar, price) {
+            let reference = reference.ok_or_else(no_reference)?;
+            if !reference.is_zero() && ((price - reference) / reference).abs() > collar {
+                return Err(RiskViolation::PriceCollar {
+                    market: market.to_string(),
+                    price,
+                    reference,
+                    collar,
+                });
+            }
+        }
+
+        if let (Some(limit), false) = (self.max_position_notional, reduce_only) {
+            let price = value_price.ok_or_else(no_reference)?;
+            let position = state.positions.get(market).cloned().unwrap_or_default();
+            let exposure = state.resting(market, side, replaces) + size;
+            let projected = match side {
+                Side::Buy => position + exposure,
+                Side::Sell => position - exposure,
+            };
+            let projected = projected.abs() * price;
+            if projected > limit && projected > position.abs() * price {
+                return Err(RiskViolation::PositionNotional {
+                    market: market.to_string(),
+                    projected,
+                    limit,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+// `OrdersClient` behind `RiskLimits`: orders that break a limit fail with
+// `ErrorKind::RiskLimitViolated` and never reach the exchange.
+#[derive(Clone)]
+pub struct RiskGuard {
+    orders: OrdersClient,
+    pub limits: RiskLimits,
+    state: Arc<Mutex<RiskState>>,
+}
+
+impl RiskGuard {
+    pub fn new(orders: OrdersClient, limits: RiskLimits) -> Self {
+        RiskGuard {
+            orders,
+            limits,
+            state: Arc::new(Mutex::new(RiskState::default())),
+        }
+    }
+
+    // replace positions and open orders with a REST snapshot
+    pub fn sync(&self, positions: &[model::Position], open_orders: &[model::OrderInfo]) {
+        let mut state = self.state.lock().unwrap();
+        state.positions = positions
+            .iter()
+            .map(|p| (p.future.clone(), p.net_size))
+            .collect();
+        state.open_orders.clear();
+        for order in open_orders {
+            state.track(&model::Order::from(order.clone()));
+        }
+    }
+
+    pub fn set_reference_price<S>(&self, market: S, price: Decimal)
+    where
+        S: Into<String>,
+    {
+        let mut state = self.state.lock().unwrap();
+        state.reference_prices.insert(market.into(), price);
+    }
+
+    pub fn on_ticker<S>(&self, market: S, ticker: &TickerInfo)
+    where
+        S: Into<String>,
+    {
+        self.set_reference_price(market, ticker.last);
+    }
+
+    pub fn on_fill(&self, fill: &model::Fill) {
+        let mut state = self.state.lock().unwrap();
+        let delta = match fill.side {
+            Side::Buy => fill.size,
+            Side::Sell => -fill.size,
+        };
+        *state.positions.entry(fill.market.clone()).or_default() += delta;
+        if let Some(order) = state.open_orders.get_mut(&fill.order_id) {
+            order.remaining = (order.remaining - fill.size).max(Decimal::ZERO);
+        }
+    }
+
+    pub fn on_order(&self, order: &model::Order) {
+        self.state.lock().unwrap().track(order);
+    }
+
+    fn check(&self, order: Candidate) -> Result<()> {
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap();
+        while state
+            .recent
+            .front()
+            .is_some_and(|t| now.duration_since(*t) >= RATE_WINDOW)
+        {
+            state.recent.pop_front();
+        }
+        if let Err(v) = self.limits.check(&state, &order, now) {
+            bail!(ErrorKind::RiskLimitViolated(v));
+        }
+        state.recent.push_back(now);
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn place_order<S>(
+        &self,
+        symbol: S,
+        side: Side,
+        price: Option<Decimal>,
+        option_type: String,
+        size: Decimal,
+        reduce_only: bool,
+        ioc: bool,
+        post_only: bool,
+        client_id: Option<String>,
+        reject_on_price_band: bool,
+    ) -> Result<model::OrderInfo>
+    where
+        S: Into<String>,
+    {
+        let symbol: String = symbol.into();
+        self.check(Candidate {
+            market: &symbol,
+            side,
+            price,
+            size,
+            reduce_only,
+            replaces: None,
+        })?;
+        let order = self.orders.place_order(
+            symbol,
+            side.as_str().to_string(),
+            price,
+            option_type,
+            size,
+            reduce_only,
+            ioc,
+            post_only,
+            client_id,
+            reject_on_price_band,
+        )?;
+        self.on_order(&model::Order::from(order.clone()));
+        Ok(order)
+    }
+
+    pub fn modify_order(
+        &self,
+        order_id: i64,
+        price: Decimal,
+        size: Decimal,
+        client_id: Option<String>,
+    ) -> Result<model::OrderInfo> {
+        let tracked = self
+            .state
+            .lock()
+            .unwrap()
+            .open_orders
+            .get(&order_id)
+            .cloned();
+        let (market, side, reduce_only) = match tracked {
+            Some(o) => (o.market, o.side, o.reduce_only),
+            None => {
+                let o = self.orders.get_order_status(order_id)?;
+                (o.market, o.side, o.reduce_only)
+            }
+        };
+        self.check(Candidate {
+            market: &market,
+            side,
+            price: Some(price),
+            size,
+            reduce_only,
+            replaces: Some(order_id),
+        })?;
+        let order = self.orders.modify_order(order_id, price, size, client_id)?;
+        let mut state = self.state.lock().unwrap();
+        state.open_orders.remove(&order_id);
+        state.track(&model::Order::from(order.clone()));
+        Ok(order)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(v: i64) -> Decimal {
+        Decimal::from(v)
+    }
+
+    fn order(market: &str, side: Side, price: i64, size: i64) -> Candidate<'_> {
+        Candidate {
+            market,
+            side,
+            price: Some(d(price)),
+            size: d(size),
+            reduce_only: false,
+            replaces: None,
+        }
+    }
+
+    fn state() -> RiskState {
+        let mut state = RiskState::default();
+        state.reference_prices.insert("BTC-PERP".into(), d(100));
+        state.positions.insert("BTC-PERP".into(), d(5));
+        state.open_orders.insert(
+            1,
+            OpenOrder {
+                market: "BTC-PERP".into(),
+                side: Side::Buy,
+                remaining: d(3),
+                reduce_only: false,
+            },
+        );
+        state
+    }
+
+    #[test]
+    fn test_check() {
+        let limits = RiskLimits {
+            max_order_notional: Some(d(1000)),
+            max_position_notional: Some(d(1000)),
+            price_collar: Some(Decimal::new(5, 2)),
+            max_open_orders: Some(2),
+            max_orders_per_minute: Some(2),
+        };
+        let state = state();
+        let now = Instant::now();
+        let check =
+            |side, price, size| limits.check(&state, &order("BTC-PERP", side, price, size), now);
+
+        assert_eq!(check(Side::Buy, 100, 2), Ok(()));
+        assert_eq!(
+            check(Side::Buy, 100, 11),
+            Err(RiskViolation::OrderNotional {
+                notional: d(1100),
+                limit: d(1000)
+            })
+        );
+        assert!(matches!(
+            check(Side::Buy, 110, 1),
+            Err(RiskViolation::PriceCollar { .. })
+        ));
+        // 5 held + 3 resting + 3 new
+        assert!(matches!(
+            check(Side::Buy, 100, 3),
+            Err(RiskViolation::PositionNotional { .. })
+        ));
+        assert_eq!(check(Side::Sell, 100, 9), Ok(()));
+        // reduce-only orders never add exposure
+        let reduce = Candidate {
+            reduce_only: true,
+            ..order("BTC-PERP", Side::Buy, 100, 3)
+        };
+        assert_eq!(limits.check(&state, &reduce, now), Ok(()));
+        // market orders are valued at the reference price
+        let market_order = Candidate {
+            price: None,
+            ..order("BTC-PERP", Side::Sell, 0, 11)
+        };
+        assert!(matches!(
+            limits.check(&state, &market_order, now),
+            Err(RiskViolation::OrderNotional { .. })
+        ));
+        assert!(matches!(
+            limits.check(&state, &order("ETH-PERP", Side::Buy, 1, 1), now),
+            Err(RiskViolation::NoReferencePrice { .. })
+        ));
+    }
+
+    #[test]
+    fn test_check_counts() {
+        let limits = RiskLimits {
+            max_open_orders: Some(1),
+            max_orders_per_minute: Some(1),
+            ..RiskLimits::default()
+        };
+        let mut state = state();
+        let now = Instant::now();
+        let new = order("BTC-PERP", Side::Buy, 100, 1);
+        assert_eq!(
+            limits.check(&state, &new, now),
+            Err(RiskViolation::OpenOrders { open: 1, limit: 1 })
+        );
+        // a modify replaces an order rather than adding one
+        let modify = Candidate {
+            replaces: Some(1),
+            ..new
+        };
+        assert_eq!(limits.check(&state, &modify, now), Ok(()));
+        state.recent.push_back(now);
+        assert_eq!(
+            limits.check(&state, &modify, now),
+            Err(RiskViolation::OrderRate { limit: 1 })
+        );
+    }
+}
@@ -0,0 +1,159 @@
+use crate::client::Client;
+use crate::errors::*;
+use crate::model;
+use crate::pagination::paginate;
+use crate::timestamps::{with_query, TimeRange};
+use rust_decimal::Decimal;
+
+#[derive(Clone)]
+pub struct SpotMarginClient {
+    client: Client,
+}
+
+impl SpotMarginClient {
+    pub fn new(api_key: Option<String>, secret_key: Option<String>) -> Self {
+        SpotMarginClient {
+            client: Client::new(api_key, secret_key),
+        }
+    }
+
+    pub fn for_subaccount<S>(&self, subaccount: S) -> Self
+    where
+        S: Into<String>,
+    {
+        SpotMarginClient {
+            client: self.client.for_subaccount(subaccount),
+        }
+    }
+
+    pub fn for_main_account(&self) -> Self {
+        SpotMarginClient {
+            client: self.client.for_main_account(),
+        }
+    }
+
+    pub fn get_lending_rates(&self) -> Result<Vec<model::SpotMarginRate>> {
+        let payload: String = "{}".into();
+        let data = self
+            .client
+            .get_signed("/spot_margin/lending_rates".into(), payload)?;
+        let rates: model::ResultData<Vec<model::SpotMarginRate>> =
+            serde_json::from_str(data.as_str())?;
+        Ok(rates.result)
+    }
+
+    pub fn get_borrow_rates(&self) -> Result<Vec<model::SpotMarginRate>> {
+        let payload: String = "{}".into();
+        let data = self
+            .client
+            .get_signed("/spot_margin/borrow_rates".into(), payload)?;
+        let rates: model::ResultData<Vec<model::SpotMarginRate>> =
+            serde_json::from_str(data.as_str())?;
+        Ok(rates.result)
+    }
+
+    // total borrowed per coin across the exchange
+    pub fn get_borrow_summary(&self) -> Result<Vec<model::BorrowSummary>> {
+        let data = self
+            .client
+            .get("/spot_margin/borrow_summary".into(), "".into())?;
+        let summary: model::ResultData<Vec<model::BorrowSummary>> =
+            serde_json::from_str(data.as_str())?;
+        Ok(summary.result)
+    }
+
+    pub fn get_market_info<S>(&self, symbol: S) -> Result<Vec<model::SpotMarginMarketInfo>>
+    where
+        S: Into<String>,
+    {
+        let payload: String = "{}".into();
+        let endpoint = with_query(
+            "/spot_margin/market_info".into(),
+            &[("market", symbol.into())],
+        );
+        let data = self.client.get_signed(endpoint, payload)?;
+        let info: model::ResultData<Vec<model::SpotMarginMarketInfo>> =
+            serde_json::from_str(data.as_str())?;
+        Ok(info.result)
+    }
+
+    pub fn get_borrow_history<R>(&self, range: R) -> Result<Vec<model::BorrowHistory>>
+    where
+        R: Into<TimeRange>,
+    {
+        let payload: String = "{}".into();
+        let endpoint = with_query("/spot_margin/borrow_history".into(), &range.into().params());
+        let data = self.client.get_signed(endpoint, payload)?;
+        let history: model::ResultData<Vec<model::BorrowHistory>> =
+            serde_json::from_str(data.as_str())?;
+        Ok(history.result)
+    }
+
+    pub fn get_all_borrow_history<R>(&self, range: R) -> Result<Vec<model::BorrowHistory>>
+    where
+        R: Into<TimeRange>,
+    {
+        paginate(
+            range.into(),
+            |page| self.get_borrow_history(page),
+            |row| row.time,
+            |row| (row.coin.clone(), row.time),
+        )
+    }
+
+    pub fn get_lending_history<R>(&self, range: R) -> Result<Vec<model::LendingHistory>>
+    where
+        R: Into<TimeRange>,
+    {
+        let payload: String = "{}".into();
+        let endpoint = with_query(
+            "/spot_margin/lending_history".into(),
+            &range.into().params(),
+        );
+        let data = self.client.get_signed(endpoint, payload)?;
+        let history: model::ResultData<Vec<model::LendingHistory>> =
+            serde_json::from_str(data.as_str())?;
+        Ok(history.result)
+    }
+
+    pub fn get_all_lending_history<R>(&self, range: R) -> Result<Vec<model::LendingHistory>>
+    where
+        R: Into<TimeRange>,
+    {
+        paginate(
+            range.into(),
+            |page| self.get_lending_history(page),
+            |row| row.time,
+            |row| (row.coin.clone(), row.time),
+        )
+    }
+
+    pub fn get_lending_offers(&self) -> Result<Vec<model::LendingOffer>> {
+        let payload: String = "{}".into();
+        let data = self
+            .client
+            .get_signed("/spot_margin/offers".into(), payload)?;
+        let offers: model::ResultData<Vec<model::LendingOffer>> =
+            serde_json::from_str(data.as_str())?;
+        Ok(offers.result)
+    }
+
+    pub fn get_lending_info(&self) -> Result<Vec<model::LendingInfo>> {
+        let payload: String = "{}".into();
+        let data = self
+            .client
+            .get_signed("/spot_margin/lending_info".into(), payload)?;
+        let info: model::ResultData<Vec<model::LendingInfo>> = serde_json::from_str(data.as_str())?;
+        Ok(info.result)
+    }
+
+    // rate is the minimum hourly rate, a size of zero withdraws the offer
+    pub fn submit_lending_offer(&self, coin: String, size: Decimal, rate: Decimal) -> Result<bool> {
+        let payload = json!({ "coin": coin, "size": size, "rate": rate });
+        let data = self
+            .client
+            .post_signed("/spot_margin/offers".into(), payload.to_string())?;
+        let r: model::AnotherOption = serde_json::from_str(data.as_str())?;
+        Ok(r.success)
+    }
+}
@@ -0,0 +1,114 @@
+use crate::client::Client;
+use crate::errors::*;
+use crate::model;
+use crate::pagination::paginate;
+use crate::timestamps::{with_query, TimeRange};
+use rust_decimal::Decimal;
+
+#[derive(Clone)]
+pub struct StakingClient {
+    client: Client,
+}
+
+impl StakingClient {
+    pub fn new(api_key: Option<String>, secret_key: Option<String>) -> Self {
+        StakingClient {
+            client: Client::new(api_key, secret_key),
+        }
+    }
+
+    pub fn for_subaccount<S>(&self, subaccount: S) -> Self
+    where
+        S: Into<String>,
+    {
+        StakingClient {
+            client: self.client.for_subaccount(subaccount),
+        }
+    }
+
+    pub fn for_main_account(&self) -> Self {
+        StakingClient {
+            client: self.client.for_main_account(),
+        }
+    }
+
+    pub fn get_stakes(&self) -> Result<Vec<model::Stake>> {
+        let payload: String = "{}".into();
+        let data = self.client.get_signed("/staking/stakes".into(), payload)?;
+        let stakes: model::ResultData<Vec<model::Stake>> = serde_json::from_str(data.as_str())?;
+        Ok(stakes.result)
+    }
+
+    // coin -> SRM/FTT/...
+    pub fn stake(&self, coin: String, size: Decimal) -> Result<model::Stake> {
+        let payload = json!({ "coin": coin, "size": size });
+        let data = self
+            .client
+            .post_signed("/srm_stakes/stakes".into(), payload.to_string())?;
+        let stake: model::ResultData<model::Stake> = serde_json::from_str(data.as_str())?;
+        Ok(stake.result)
+    }
+
+    pub fn get_stake_balances(&self) -> Result<Vec<model::StakeBalance>> {
+        let payload: String = "{}".into();
+        let data = self
+            .client
+            .get_signed("/staking/balances".into(), payload)?;
+        let balances: model::ResultData<Vec<model::StakeBalance>> =
+            serde_json::from_str(data.as_str())?;
+        Ok(balances.result)
+    }
+
+    pub fn get_unstake_requests(&self) -> Result<Vec<model::UnstakeRequest>> {
+        let payload: String = "{}".into();
+        let data = self
+            .client
+            .get_signed("/staking/unstake_requests".into(), payload)?;
+        let requests: model::ResultData<Vec<model::UnstakeRequest>> =
+            serde_json::from_str(data.as_str())?;
+        Ok(requests.result)
+    }
+
+    pub fn request_unstake(&self, coin: String, size: Decimal) -> Result<model::UnstakeRequest> {
+        let payload = json!({ "coin": coin, "size": size });
+        let data = self
+            .client
+            .post_signed("/staking/unstake_requests".into(), payload.to_string())?;
+        let request: model::ResultData<model::UnstakeRequest> =
+            serde_json::from_str(data.as_str())?;
+        Ok(request.result)
+    }
+
+    pub fn cancel_unstake_request(&self, request_id: i64) -> Result<bool> {
+        let payload: String = "{}".into();
+        let endpoint = format!("/staking/unstake_requests/{}", request_id);
+        let data = self.client.delete_signed(endpoint, payload)?;
+        let result: model::ResultData<String> = serde_json::from_str(data.as_str())?;
+        Ok(result.success)
+    }
+
+    pub fn get_staking_rewards<R>(&self, range: R) -> Result<Vec<model::StakingReward>>
+    where
+        R: Into<TimeRange>,
+    {
+        let payload: String = "{}".into();
+        let endpoint = with_query("/staking/staking_rewards".into(), &range.into().params());
+        let data = self.client.get_signed(endpoint, payload)?;
+        let rewards: model::ResultData<Vec<model::StakingReward>> =
+            serde_json::from_str(data.as_str())?;
+        Ok(rewards.result)
+    }
+
+    // pages through the whole range, newest first
+    pub fn get_all_staking_rewards<R>(&self, range: R) -> Result<Vec<model::StakingReward>>
+    where
+        R: Into<TimeRange>,
+    {
+        paginate(
+            range.into(),
+            |page| self.get_staking_rewards(page),
+            |reward| reward.time,
+            |reward| reward.id,
+        )
+    }
+}
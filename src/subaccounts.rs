@@ -1,6 +1,10 @@
 use crate::client::Client;
 use crate::errors::*;
 use crate::model;
+use rust_decimal::Decimal;
+
+// name FTX uses for the main account in transfers
+pub static MAIN_ACCOUNT: &str = "main";
 
 #[derive(Clone)]
 pub struct SubAccountsClient {
@@ -16,9 +20,7 @@ impl SubAccountsClient {
 
     pub fn get_subaccounts(&self) -> Result<Vec<model::SubAccountInfo>> {
         let payload: String = format!("{}", "{}");
-        let data = self
-            .client
-            .get_signed("/subaccounts".into(), payload, None)?;
+        let data = self.client.get_signed("/subaccounts".into(), payload)?;
         let subaccounts: model::ResultData<Vec<model::SubAccountInfo>> =
             serde_json::from_str(data.as_str())?;
         Ok(subaccounts.result)
@@ -28,7 +30,7 @@ impl SubAccountsClient {
         let payload = json!({ "nickname": nickname });
         let data = self
             .client
-            .post_signed("/subaccounts".into(), payload.to_string(), None)?;
+            .post_signed("/subaccounts".into(), payload.to_string())?;
         let subaccount: model::ResultData<model::SubAccountInfo> =
             serde_json::from_str(data.as_str())?;
         Ok(subaccount.result)
@@ -36,11 +38,9 @@ impl SubAccountsClient {
 
     pub fn change_subaccount_name(&self, nickname: String, new_nickname: String) -> Result<bool> {
         let payload = json!({ "nickname": nickname, "newNickname": new_nickname });
-        let data = self.client.post_signed(
-            "/subaccounts/update_name".into(),
-            payload.to_string(),
-            None,
-        )?;
+        let data = self
+            .client
+            .post_signed("/subaccounts/update_name".into(), payload.to_string())?;
         let r: model::ResultData<model::SubAccountInfo> = serde_json::from_str(data.as_str())?;
         Ok(r.success)
     }
@@ -49,7 +49,7 @@ impl SubAccountsClient {
         let payload = json!({ "nickname": nickname });
         let data = self
             .client
-            .delete_signed("/subaccounts".into(), payload.to_string(), None)?;
+            .delete_signed("/subaccounts".into(), payload.to_string())?;
         let r: model::ResultData<model::SubAccountInfo> = serde_json::from_str(data.as_str())?;
         Ok(r.success)
     }
@@ -61,7 +61,7 @@ impl SubAccountsClient {
         let payload: String = format!("{}", "{}");
 
         let endpoint = format!("/subaccounts/{}/balances", nickname);
-        let data = self.client.get_signed(endpoint.into(), payload, None)?;
+        let data = self.client.get_signed(endpoint.into(), payload)?;
         let subaccount_balances: model::ResultData<Vec<model::SubAccountBalanceInfo>> =
             serde_json::from_str(data.as_str())?;
         Ok(subaccount_balances.result)
@@ -70,15 +70,15 @@ impl SubAccountsClient {
     pub fn transfer_subaccounts(
         &self,
         coin: String,
-        size: f64,
+        size: Decimal,
         source: String,
         destination: String,
     ) -> Result<model::TransferInfo> {
         let payload =
             json!({ "coin": coin, "size": size, "source": source, "destination": destination });
-        let data =
-            self.client
-                .post_signed("/subaccounts/transfer".into(), payload.to_string(), None)?;
+        let data = self
+            .client
+            .post_signed("/subaccounts/transfer".into(), payload.to_string())?;
         let subacounts_transfer: model::ResultData<model::TransferInfo> =
             serde_json::from_str(data.as_str())?;
         Ok(subacounts_transfer.result)
@@ -0,0 +1,159 @@
+use crate::client::uri_encode;
+use chrono::{DateTime, TimeZone, Utc};
+use std::ops::{Range, RangeFrom, RangeFull, RangeTo};
+
+/// Serde adapter for timestamps sent as fractional unix seconds, e.g. `1589836522.5312212`.
+pub mod float_seconds {
+    use chrono::{DateTime, TimeZone, Utc};
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(time: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let seconds =
+            time.timestamp() as f64 + f64::from(time.timestamp_subsec_nanos()) / 1_000_000_000f64;
+        serializer.serialize_f64(seconds)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let seconds = f64::deserialize(deserializer)?;
+        let whole = seconds.floor();
+        let nanos = ((seconds - whole) * 1_000_000_000f64).round() as u32;
+        Utc.timestamp_opt(whole as i64, nanos.min(999_999_999))
+            .single()
+            .ok_or_else(|| de::Error::custom(format!("invalid timestamp: {}", seconds)))
+    }
+}
+
+/// Time window for endpoints that accept `start_time`/`end_time`.
+///
+/// Built from a `DateTime<Utc>` (everything since then) or any of the std ranges,
+/// so `start..end`, `start..`, `..end` and `..` all work.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TimeRange {
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+}
+
+impl TimeRange {
+    pub fn new(start: Option<DateTime<Utc>>, end: Option<DateTime<Utc>>) -> Self {
+        TimeRange { start, end }
+    }
+
+    /// Query parameters in FTX's unix-seconds format, empty when unbounded.
+    pub fn params(&self) -> Vec<(&'static str, String)> {
+        let mut params = Vec::new();
+        if let Some(start) = self.start {
+            params.push(("start_time", start.timestamp().to_string()));
+        }
+        if let Some(end) = self.end {
+            params.push(("end_time", end.timestamp().to_string()));
+        }
+        params
+    }
+
+    /// Query string fragment built from `params`.
+    pub fn to_query(&self) -> String {
+        self.params()
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect::<Vec<String>>()
+            .join("&")
+    }
+}
+
+impl From<DateTime<Utc>> for TimeRange {
+    fn from(start: DateTime<Utc>) -> Self {
+        TimeRange::new(Some(start), None)
+    }
+}
+
+impl From<Range<DateTime<Utc>>> for TimeRange {
+    fn from(r: Range<DateTime<Utc>>) -> Self {
+        TimeRange::new(Some(r.start), Some(r.end))
+    }
+}
+
+impl From<RangeFrom<DateTime<Utc>>> for TimeRange {
+    fn from(r: RangeFrom<DateTime<Utc>>) -> Self {
+        TimeRange::new(Some(r.start), None)
+    }
+}
+
+impl From<RangeTo<DateTime<Utc>>> for TimeRange {
+    fn from(r: RangeTo<DateTime<Utc>>) -> Self {
+        TimeRange::new(None, Some(r.end))
+    }
+}
+
+impl From<RangeFull> for TimeRange {
+    fn from(_: RangeFull) -> Self {
+        TimeRange::default()
+    }
+}
+
+/// Build an endpoint with `params` appended as a query string, values percent-encoded.
+pub(crate) fn with_query(endpoint: String, params: &[(&str, String)]) -> String {
+    let query: Vec<String> = params
+        .iter()
+        .map(|(k, v)| format!("{}={}", k, uri_encode(v)))
+        .collect();
+    if query.is_empty() {
+        endpoint
+    } else {
+        format!("{}?{}", endpoint, query.join("&"))
+    }
+}
+
+pub(crate) fn from_seconds(seconds: i64) -> DateTime<Utc> {
+    Utc.timestamp_opt(seconds, 0).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(serde::Deserialize)]
+    struct Stamped {
+        #[serde(with = "float_seconds")]
+        time: DateTime<Utc>,
+    }
+
+    #[test]
+    fn test_float_seconds() {
+        let s: Stamped = serde_json::from_str(r#"{"time": 1589836522.5312212}"#).unwrap();
+        assert_eq!(s.time.timestamp(), 1589836522);
+        assert_eq!(s.time.timestamp_subsec_millis(), 531);
+    }
+
+    #[test]
+    fn test_time_range_query() {
+        let start = from_seconds(1559881511);
+        let end = from_seconds(1559901511);
+        assert_eq!(
+            TimeRange::from(start..end).to_query(),
+            "start_time=1559881511&end_time=1559901511"
+        );
+        assert_eq!(TimeRange::from(..end).to_query(), "end_time=1559901511");
+        assert_eq!(TimeRange::from(..).to_query(), "");
+        assert_eq!(
+            with_query("/fills".into(), &[("market", "BTC-PERP".into())]),
+            "/fills?market=BTC-PERP"
+        );
+        assert_eq!(
+            with_query("/fills".into(), &TimeRange::default().params()),
+            "/fills"
+        );
+        assert_eq!(
+            with_query(
+                "/wallet/withdrawal_fee".into(),
+                &[("address", "a b".into()), ("tag", "1&x=#+".into())]
+            ),
+            "/wallet/withdrawal_fee?address=a%20b&tag=1%26x%3D%23%2B"
+        );
+    }
+}
@@ -1,6 +1,8 @@
 use crate::client::Client;
 use crate::errors::*;
 use crate::model;
+use crate::timestamps::{with_query, TimeRange};
+use rust_decimal::Decimal;
 use std::collections::HashMap;
 
 #[derive(Clone)]
@@ -15,6 +17,21 @@ impl WalletClient {
         }
     }
 
+    pub fn for_subaccount<S>(&self, subaccount: S) -> Self
+    where
+        S: Into<String>,
+    {
+        WalletClient {
+            client: self.client.for_subaccount(subaccount),
+        }
+    }
+
+    pub fn for_main_account(&self) -> Self {
+        WalletClient {
+            client: self.client.for_main_account(),
+        }
+    }
+
     pub fn get_coins(&self) -> Result<Vec<model::CoinInfo>> {
         let data = self.client.get("/wallet/coins".into(), "".into())?;
         let coins: model::Coins = serde_json::from_str(data.as_str())?;
@@ -23,9 +40,7 @@ impl WalletClient {
 
     pub fn get_balances(&self) -> Result<Vec<model::BalanceInfo>> {
         let payload: String = format!("{}", "{}");
-        let data = self
-            .client
-            .get_signed("/wallet/balances".into(), payload, None)?;
+        let data = self.client.get_signed("/wallet/balances".into(), payload)?;
         let balances: model::ResultData<Vec<model::BalanceInfo>> =
             serde_json::from_str(data.as_str())?;
         Ok(balances.result)
@@ -35,7 +50,7 @@ impl WalletClient {
         let payload: String = format!("{}", "{}");
         let data = self
             .client
-            .get_signed("/wallet/all_balances".into(), payload, None)?;
+            .get_signed("/wallet/all_balances".into(), payload)?;
         let all_balances: model::ResultData<HashMap<String, Vec<model::BalanceInfo>>> =
             serde_json::from_str(data.as_str())?;
         Ok(all_balances.result)
@@ -46,69 +61,144 @@ impl WalletClient {
         coin: String,
         method: Option<String>,
     ) -> Result<model::Address> {
-        let mut endpoint = format!("/wallet/deposit_address/{}", &coin);
-        if let Some(m) = method {
-            endpoint = format!("/wallet/deposit_address/{}?method={}", coin, m);
-        }
+        let params: Vec<(&str, String)> = method.map(|m| ("method", m)).into_iter().collect();
+        let endpoint = with_query(format!("/wallet/deposit_address/{}", coin), &params);
         let payload: String = format!("{}", "{}");
         let data = self
             .client
-            .get_signed(endpoint.into(), payload.to_string(), None)?;
+            .get_signed(endpoint.into(), payload.to_string())?;
         let address: model::ResultData<model::Address> = serde_json::from_str(data.as_str())?;
         Ok(address.result)
     }
 
-    pub fn get_deposits(
-        &self,
-        start_time: Option<i64>,
-        end_time: Option<i64>,
-    ) -> Result<Vec<model::Deposit>> {
-        let mut payload: String = format!("{}", "{}");
-        if start_time != None && end_time != None {
-            payload = json!({ "start_time": start_time.unwrap(), "end_time": end_time.unwrap() })
-                .to_string();
-        }
-        let data = self
-            .client
-            .get_signed("/wallet/deposits".into(), payload.to_string(), None)?;
+    pub fn get_deposits<R>(&self, range: R) -> Result<Vec<model::Deposit>>
+    where
+        R: Into<TimeRange>,
+    {
+        let payload: String = "{}".into();
+        let endpoint = with_query("/wallet/deposits".into(), &range.into().params());
+        let data = self.client.get_signed(endpoint, payload)?;
         let deposits: model::ResultData<Vec<model::Deposit>> = serde_json::from_str(data.as_str())?;
         Ok(deposits.result)
     }
 
-    pub fn get_withdrawals(
-        &self,
-        start_time: Option<i64>,
-        end_time: Option<i64>,
-    ) -> Result<Vec<model::Withdrawal>> {
-        let mut payload: String = format!("{}", "{}");
-        if start_time != None && end_time != None {
-            payload = json!({ "start_time": start_time.unwrap(), "end_time": end_time.unwrap() })
-                .to_string();
-        }
-        let data =
-            self.client
-                .get_signed("/wallet/withdrawals".into(), payload.to_string(), None)?;
+    pub fn get_withdrawals<R>(&self, range: R) -> Result<Vec<model::Withdrawal>>
+    where
+        R: Into<TimeRange>,
+    {
+        let payload: String = "{}".into();
+        let endpoint = with_query("/wallet/withdrawals".into(), &range.into().params());
+        let data = self.client.get_signed(endpoint, payload)?;
         let withdrawals: model::ResultData<Vec<model::Withdrawal>> =
             serde_json::from_str(data.as_str())?;
         Ok(withdrawals.result)
     }
 
-    pub fn get_airdrops(
+    pub fn get_airdrops<R>(&self, range: R) -> Result<Vec<model::Airdrops>>
+    where
+        R: Into<TimeRange>,
+    {
+        let payload: String = "{}".into();
+        let endpoint = with_query("/wallet/airdrops".into(), &range.into().params());
+        let data = self.client.get_signed(endpoint, payload)?;
+        let airdrops: model::ResultData<Vec<model::Airdrops>> =
+            serde_json::from_str(data.as_str())?;
+        Ok(airdrops.result)
+    }
+
+    pub fn request_withdrawal(
         &self,
-        start_time: Option<i64>,
-        end_time: Option<i64>,
-    ) -> Result<Vec<model::Airdrops>> {
-        let mut payload: String = format!("{}", "{}");
-        if start_time != None && end_time != None {
-            payload = json!({ "start_time": start_time.unwrap(), "end_time": end_time.unwrap() })
-                .to_string();
-        }
+        request: &model::WithdrawalRequest,
+    ) -> Result<model::Withdrawal> {
+        let payload = serde_json::to_string(request)?;
         let data = self
             .client
-            .get_signed("/wallet/airdrops".into(), payload.to_string(), None)?;
-        let airdrops: model::ResultData<Vec<model::Airdrops>> =
+            .post_signed("/wallet/withdrawals".into(), payload)?;
+        let withdrawal: model::ResultData<model::Withdrawal> = serde_json::from_str(data.as_str())?;
+        Ok(withdrawal.result)
+    }
+
+    pub fn get_withdrawal_fees(
+        &self,
+        coin: String,
+        size: Decimal,
+        address: String,
+        tag: Option<String>,
+        method: Option<String>,
+    ) -> Result<model::WithdrawalFee> {
+        let payload: String = "{}".into();
+        let mut params = vec![
+            ("coin", coin),
+            ("size", size.to_string()),
+            ("address", address),
+        ];
+        if let Some(t) = tag {
+            params.push(("tag", t));
+        }
+        if let Some(m) = method {
+            params.push(("method", m));
+        }
+        let endpoint = with_query("/wallet/withdrawal_fee".into(), &params);
+        let data = self.client.get_signed(endpoint, payload)?;
+        let fee: model::ResultData<model::WithdrawalFee> = serde_json::from_str(data.as_str())?;
+        Ok(fee.result)
+    }
+
+    // dry run: the fee `request_withdrawal` would charge, without submitting anything
+    pub fn preview_withdrawal(
+        &self,
+        request: &model::WithdrawalRequest,
+    ) -> Result<model::WithdrawalFee> {
+        self.get_withdrawal_fees(
+            request.coin.clone(),
+            request.size,
+            request.address.clone(),
+            request.tag.clone(),
+            request.method.clone(),
+        )
+    }
+
+    pub fn get_saved_addresses(&self, coin: Option<String>) -> Result<Vec<model::SavedAddress>> {
+        let payload: String = "{}".into();
+        let mut params = Vec::new();
+        if let Some(c) = coin {
+            params.push(("coin", c));
+        }
+        let endpoint = with_query("/wallet/saved_addresses".into(), &params);
+        let data = self.client.get_signed(endpoint, payload)?;
+        let addresses: model::ResultData<Vec<model::SavedAddress>> =
             serde_json::from_str(data.as_str())?;
-        Ok(airdrops.result)
+        Ok(addresses.result)
+    }
+
+    pub fn create_saved_address(
+        &self,
+        coin: String,
+        address: String,
+        address_name: String,
+        is_prime_trust: bool,
+        tag: Option<String>,
+    ) -> Result<model::SavedAddress> {
+        let payload = json!({
+            "coin": coin,
+            "address": address,
+            "addressName": address_name,
+            "isPrimeTrust": is_prime_trust,
+            "tag": tag,
+        });
+        let data = self
+            .client
+            .post_signed("/wallet/saved_addresses".into(), payload.to_string())?;
+        let address: model::ResultData<model::SavedAddress> = serde_json::from_str(data.as_str())?;
+        Ok(address.result)
+    }
+
+    pub fn delete_saved_address(&self, saved_address_id: i64) -> Result<bool> {
+        let payload: String = "{}".into();
+        let endpoint = format!("/wallet/saved_addresses/{}", saved_address_id);
+        let data = self.client.delete_signed(endpoint, payload)?;
+        let result: model::ResultData<String> = serde_json::from_str(data.as_str())?;
+        Ok(result.success)
     }
 }
 
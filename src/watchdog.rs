@@ -0,0 +1,248 @@
+use crate::errors::*;
+use crate::model;
+use crate::orders::OrdersClient;
+use chrono::{DateTime, Utc};
+use std::collections::BTreeSet;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+// Which orders the watchdog cancels; the default covers every open order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WatchdogScope {
+    pub market: Option<String>,
+    pub client_id_prefix: Option<String>,
+}
+
+impl WatchdogScope {
+    pub fn matches(&self, order: &model::OrderInfo) -> bool {
+        let market = self.market.as_ref().is_none_or(|m| *m == order.market);
+        let client_id = match &self.client_id_prefix {
+            Some(prefix) => order
+                .client_id
+                .as_ref()
+                .is_some_and(|c| c.starts_with(prefix.as_str())),
+            None => true,
+        };
+        market && client_id
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TripReason {
+    MissedHeartbeat,
+    Disconnected,
+}
+
+#[derive(Debug, Clone)]
+pub struct WatchdogTrip {
+    pub reason: TripReason,
+    pub time: DateTime<Utc>,
+    // markets swept with cancel_all_orders, or single orders cancelled by id
+    pub cancelled_markets: Vec<String>,
+    pub cancelled_orders: Vec<i64>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct Cancelled {
+    pub markets: Vec<String>,
+    pub orders: Vec<i64>,
+}
+
+enum Signal {
+    Heartbeat,
+    Disconnected,
+    Stop,
+}
+
+// Cheap to clone; give one to every part of the application that proves it is alive.
+#[derive(Clone)]
+pub struct WatchdogHandle {
+    tx: mpsc::Sender<Signal>,
+}
+
+impl WatchdogHandle {
+    pub fn heartbeat(&self) {
+        let _ = self.tx.send(Signal::Heartbeat);
+    }
+
+    // `WebSockets::set_watchdog` calls this when `event_loop` returns
+    pub fn disconnected(&self) {
+        let _ = self.tx.send(Signal::Disconnected);
+    }
+}
+
+// Dead man's switch: unless `heartbeat` is called at least every `timeout`, or
+// as soon as `disconnected` is called, open orders in `scope` are cancelled.
+// After tripping it stays quiet until the next heartbeat re-arms it.
+pub struct Watchdog {
+    handle: WatchdogHandle,
+    trips: Arc<Mutex<Vec<WatchdogTrip>>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Watchdog {
+    pub fn start(orders: OrdersClient, timeout: Duration, scope: WatchdogScope) -> Self {
+        Self::spawn(timeout, move || cancel_scoped(&orders, &scope))
+    }
+
+    fn spawn<F>(timeout: Duration, cancel: F) -> Self
+    where
+        F: Fn() -> Result<Cancelled> + Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel();
+        let trips = Arc::new(Mutex::new(Vec::new()));
+        let log = trips.clone();
+        let thread = thread::spawn(move || {
+            let mut armed = true;
+            loop {
+                let signal = if armed {
+                    rx.recv_timeout(timeout)
+                } else {
+                    rx.recv().map_err(|_| RecvTimeoutError::Disconnected)
+                };
+                let reason = match signal {
+                    Ok(Signal::Heartbeat) => {
+                        armed = true;
+                        continue;
+                    }
+                    Ok(Signal::Disconnected) if armed => TripReason::Disconnected,
+                    Ok(Signal::Disconnected) => continue,
+                    Err(RecvTimeoutError::Timeout) => TripReason::MissedHeartbeat,
+                    Ok(Signal::Stop) | Err(RecvTimeoutError::Disconnected) => return,
+                };
+                armed = false;
+                let mut trip = WatchdogTrip {
+                    reason,
+                    time: Utc::now(),
+                    cancelled_markets: Vec::new(),
+                    cancelled_orders: Vec::new(),
+                    error: None,
+                };
+                match cancel() {
+                    Ok(c) => {
+                        trip.cancelled_markets = c.markets;
+                        trip.cancelled_orders = c.orders;
+                    }
+                    Err(e) => trip.error = Some(e.to_string()),
+                }
+                log.lock().unwrap().push(trip);
+            }
+        });
+        Watchdog {
+            handle: WatchdogHandle { tx },
+            trips,
+            thread: Some(thread),
+        }
+    }
+
+    pub fn handle(&self) -> WatchdogHandle {
+        self.handle.clone()
+    }
+
+    pub fn heartbeat(&self) {
+        self.handle.heartbeat();
+    }
+
+    pub fn trips(&self) -> Vec<WatchdogTrip> {
+        self.trips.lock().unwrap().clone()
+    }
+
+    // disarm without cancelling anything
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        let _ = self.handle.tx.send(Signal::Stop);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for Watchdog {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+// Whole markets are swept with cancel_all_orders, trigger orders included. A
+// client id prefix can only be honoured order by order.
+pub fn cancel_scoped(orders: &OrdersClient, scope: &WatchdogScope) -> Result<Cancelled> {
+    let mut cancelled = Cancelled::default();
+    if scope.client_id_prefix.is_some() {
+        for order in orders.get_all_open_orders()? {
+            if scope.matches(&order) && orders.cancel_order(order.id)? {
+                cancelled.orders.push(order.id);
+            }
+        }
+        return Ok(cancelled);
+    }
+
+    let markets: BTreeSet<String> = match &scope.market {
+        Some(m) => [m.clone()].into_iter().collect(),
+        None => orders
+            .get_all_open_orders()?
+            .into_iter()
+            .map(|o| o.market)
+            .chain(
+                orders
+                    .get_all_open_trigger_orders()?
+                    .into_iter()
+                    .map(|o| o.market),
+            )
+            .collect(),
+    };
+    for market in markets {
+        if orders.cancel_all_orders(Some(market.clone()), None, false, false)? {
+            cancelled.markets.push(market);
+        }
+    }
+    Ok(cancelled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn watchdog(timeout: u64) -> (Watchdog, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let watchdog = Watchdog::spawn(Duration::from_millis(timeout), move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Ok(Cancelled::default())
+        });
+        (watchdog, calls)
+    }
+
+    #[test]
+    fn test_missed_heartbeat() {
+        let (watchdog, calls) = watchdog(100);
+        for _ in 0..5 {
+            thread::sleep(Duration::from_millis(10));
+            watchdog.heartbeat();
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+        // trips once, then waits for a heartbeat to re-arm
+        thread::sleep(Duration::from_millis(400));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(watchdog.trips()[0].reason, TripReason::MissedHeartbeat);
+        watchdog.stop();
+    }
+
+    #[test]
+    fn test_disconnect() {
+        let (watchdog, calls) = watchdog(10_000);
+        watchdog.handle().disconnected();
+        thread::sleep(Duration::from_millis(50));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(watchdog.trips()[0].reason, TripReason::Disconnected);
+        watchdog.stop();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -9,6 +9,7 @@ use url::Url;
 
 use crate::errors::*;
 use crate::events::*;
+use crate::watchdog::WatchdogHandle;
 use chrono::Local;
 
 static SUBSCRIBED: &'static str = "subscribed";
@@ -39,6 +40,7 @@ pub struct WebSockets {
     rx: mpsc::Receiver<WsMessage>,
     event_handler: Option<Box<dyn EventHandler>>,
     login_status: bool,
+    watchdog: Option<WatchdogHandle>,
 }
 
 impl WebSockets {
@@ -54,6 +56,7 @@ impl WebSockets {
             rx: rx,
             event_handler: None,
             login_status: false,
+            watchdog: None,
         }
     }
 
@@ -87,6 +90,11 @@ impl WebSockets {
         self.event_handler = Some(Box::new(handler));
     }
 
+    // tell `watchdog` whenever `event_loop` returns, cleanly or with an error
+    pub fn set_watchdog(&mut self, watchdog: WatchdogHandle) {
+        self.watchdog = Some(watchdog);
+    }
+
     pub fn ping(&mut self) {
         let msg = json!({
             "op": "ping",
@@ -241,6 +249,14 @@ impl WebSockets {
     }
 
     pub fn event_loop(&mut self) -> Result<()> {
+        let result = self.run();
+        if let Some(ref watchdog) = self.watchdog {
+            watchdog.disconnected();
+        }
+        result
+    }
+
+    fn run(&mut self) -> Result<()> {
         let mut ping_flag = 0;
         loop {
             if let Some(ref mut socket) = self.socket {